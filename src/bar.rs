@@ -1,14 +1,12 @@
-use crate::CONFIG;
 use crate::{
-    display::{get_display_by_hmonitor, Display},
+    display::{get_display_by_hmonitor_or_fallback, Display},
     util,
     window::Window,
 };
-use font::load_font;
+use crate::{event::Event, CHANNEL, CONFIG};
 use lazy_static::lazy_static;
-use log::info;
 
-use std::sync::Mutex;
+use std::{collections::HashMap, sync::Mutex};
 use winapi::shared::minwindef::LPARAM;
 use winapi::shared::minwindef::LRESULT;
 use winapi::shared::minwindef::UINT;
@@ -36,7 +34,6 @@ use winapi::um::winuser::SetCursor;
 use winapi::um::winuser::IDC_ARROW;
 use winapi::um::winuser::PAINTSTRUCT;
 use winapi::um::winuser::WM_CLOSE;
-use winapi::um::winuser::WM_CREATE;
 use winapi::um::winuser::WM_DEVICECHANGE;
 use winapi::um::winuser::WM_LBUTTONDOWN;
 use winapi::um::winuser::WM_PAINT;
@@ -56,7 +53,8 @@ pub mod visibility;
 
 lazy_static! {
     pub static ref BARS: Mutex<Vec<Bar>> = Mutex::new(Vec::new());
-    pub static ref FONT: Mutex<i32> = Mutex::new(0);
+    /// Fonts of the bar per dpi
+    pub static ref FONTS: Mutex<HashMap<u32, i32>> = Mutex::new(HashMap::new());
 }
 
 #[derive(Clone)]
@@ -106,17 +104,11 @@ unsafe fn draw_component_text(hdc: HDC, rect: &mut RECT, component_text: &Compon
 
     let c_text = util::to_widestring(&text);
 
-    let fg = component_text
-        .get_fg()
-        .unwrap_or(if CONFIG.lock().unwrap().light_theme {
-            0x00333333
-        } else {
-            0x00ffffff
-        });
+    let theme = CONFIG.lock().unwrap().get_theme();
+
+    let fg = component_text.get_fg().unwrap_or(theme.foreground as u32);
 
-    let bg = component_text
-        .get_bg()
-        .unwrap_or(CONFIG.lock().unwrap().bar.color as u32);
+    let bg = component_text.get_bg().unwrap_or(theme.background as u32);
 
     SetTextColor(hdc, fg);
     SetBkColor(hdc, bg);
@@ -188,7 +180,7 @@ unsafe fn components_to_section(
 }
 
 unsafe fn clear_section(hdc: HDC, height: i32, left: i32, right: i32) {
-    let brush = CreateSolidBrush(CONFIG.lock().unwrap().bar.color as u32);
+    let brush = CreateSolidBrush(CONFIG.lock().unwrap().get_theme().background as u32);
     let mut rect = RECT {
         left,
         right,
@@ -230,7 +222,7 @@ unsafe extern "system" fn window_cb(
         GetCursorPos(&mut point);
 
         let bar = get_bar_by_hwnd(hwnd as i32).unwrap();
-        let display = get_display_by_hmonitor(bar.hmonitor);
+        let display = get_display_by_hmonitor_or_fallback(bar.hmonitor);
         let x = point.x - display.left;
         let mut found = false;
 
@@ -254,13 +246,17 @@ unsafe extern "system" fn window_cb(
             SetCursor(LoadCursorA(std::ptr::null_mut(), IDC_ARROW as *const i8));
         }
     } else if msg == WM_DEVICECHANGE {
-        println!("Device change!");
+        CHANNEL
+            .sender
+            .clone()
+            .send(Event::DisplayChange)
+            .expect("Failed to send display-change event");
     } else if msg == WM_LBUTTONDOWN {
         let mut point = POINT::default();
         GetCursorPos(&mut point);
 
         let bar = get_bar_by_hwnd(hwnd as i32).unwrap();
-        let display = get_display_by_hmonitor(bar.hmonitor);
+        let display = get_display_by_hmonitor_or_fallback(bar.hmonitor);
         let x = point.x - display.left;
 
         for section in vec![bar.left, bar.center, bar.right] {
@@ -280,21 +276,19 @@ unsafe extern "system" fn window_cb(
                 }
             }
         }
-    } else if msg == WM_CREATE {
-        info!("loading font");
-        load_font();
     } else if msg == WM_PAINT {
         let bar_config = CONFIG.lock().unwrap().bar.clone();
         let mut paint = PAINTSTRUCT::default();
 
         let mut bar = get_bar_by_hwnd(hwnd as i32).unwrap();
-        let display = get_display_by_hmonitor(bar.hmonitor);
+        let display = get_display_by_hmonitor_or_fallback(bar.hmonitor);
+        let height = display.scale(bar_config.height);
 
         BeginPaint(hwnd, &mut paint);
 
         let hdc = GetDC(hwnd);
 
-        font::set_font(hdc);
+        font::set_font(hdc, display.dpi);
 
         let left = components_to_section(hdc, &display, &bar_config.components.left);
 
@@ -309,47 +303,37 @@ unsafe extern "system" fn window_cb(
         draw_components(
             hdc,
             &display,
-            bar_config.height,
+            height,
             left.left,
             &bar_config.components.left,
         );
         draw_components(
             hdc,
             &display,
-            bar_config.height,
+            height,
             center.left,
             &bar_config.components.center,
         );
         draw_components(
             hdc,
             &display,
-            bar_config.height,
+            height,
             right.left,
             &bar_config.components.right,
         );
 
         if bar.left.width() > left.width() {
-            clear_section(hdc, bar_config.height, left.right, bar.left.right);
+            clear_section(hdc, height, left.right, bar.left.right);
         }
 
         if bar.center.width() > center.width() {
             let delta = (bar.center.right - center.right) / 2;
-            clear_section(
-                hdc,
-                bar_config.height,
-                bar.center.left,
-                bar.center.left + delta,
-            );
-            clear_section(
-                hdc,
-                bar_config.height,
-                bar.center.right - delta,
-                bar.center.right,
-            );
+            clear_section(hdc, height, bar.center.left, bar.center.left + delta);
+            clear_section(hdc, height, bar.center.right - delta, bar.center.right);
         }
 
         if bar.right.width() > right.width() {
-            clear_section(hdc, bar_config.height, bar.right.left, right.left);
+            clear_section(hdc, height, bar.right.left, right.left);
         }
 
         bar.left = left;
@@ -1,4 +1,4 @@
-use super::{get_windows, FONT};
+use super::{get_windows, FONTS};
 use log::{debug, info};
 use std::ffi::CString;
 use winapi::um::winuser::UnregisterClassA;
@@ -20,6 +20,6 @@ pub fn close() {
             winapi::um::libloaderapi::GetModuleHandleA(std::ptr::null_mut()),
         );
 
-        *FONT.lock().unwrap() = 0;
+        FONTS.lock().unwrap().clear();
     }
 }
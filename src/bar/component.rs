@@ -1,4 +1,5 @@
 use crate::display::Display;
+use serde::{Serialize, Serializer};
 use std::{fmt::Debug, sync::Arc};
 
 pub mod active_mode;
@@ -8,6 +9,8 @@ pub mod padding;
 pub mod time;
 pub mod workspaces;
 
+// the text only gets drawn by the bar, which doesn't exist on other platforms
+#[cfg_attr(not(windows), allow(dead_code))]
 #[derive(Debug, Clone)]
 pub enum ComponentText {
     Basic(String),
@@ -15,6 +18,7 @@ pub enum ComponentText {
     Colored(Option<u32>, Option<u32>, String),
 }
 
+#[cfg(windows)]
 impl ComponentText {
     pub fn get_text(&self) -> String {
         match self {
@@ -42,6 +46,8 @@ pub type RenderFn = Arc<dyn Fn(&Component, &Display) -> Vec<ComponentText> + Sen
 /// Receives the Component, the display and the idx of ComponentText which got clicked
 pub type OnClickFn = Arc<dyn Fn(&Component, &Display, usize) -> () + Send + Sync>;
 
+// components only get rendered by the bar, which doesn't exist on other platforms
+#[cfg_attr(not(windows), allow(dead_code))]
 #[derive(Clone)]
 pub struct Component {
     pub name: String,
@@ -71,12 +77,14 @@ impl Component {
         }
     }
 
+    #[cfg(windows)]
     pub fn on_click(&self, display: &Display, idx: usize) {
         if let Some(fun) = self.on_click_fn.clone() {
             fun(self, display, idx);
         }
     }
 
+    #[cfg(windows)]
     pub fn render(&self, display: &Display) -> Vec<ComponentText> {
         let f = self.render_fn.clone();
 
@@ -90,6 +98,13 @@ impl Component {
     }
 }
 
+/// Components get serialized by their name, because the functions can't be serialized
+impl Serialize for Component {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.name)
+    }
+}
+
 impl Debug for Component {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.write_str(&format!(
@@ -1,7 +1,10 @@
 use super::{Component, ComponentText};
-use crate::{display::Display, with_current_grid};
+use crate::display::Display;
+#[cfg(windows)]
+use crate::with_current_grid;
 use std::sync::Arc;
 
+#[cfg(windows)]
 fn render(_: &Component, _: &Display) -> Vec<ComponentText> {
     with_current_grid(|grid| {
         vec![grid
@@ -11,6 +14,11 @@ fn render(_: &Component, _: &Display) -> Vec<ComponentText> {
     })
 }
 
+#[cfg(not(windows))]
+fn render(_: &Component, _: &Display) -> Vec<ComponentText> {
+    vec![ComponentText::Basic("".into())]
+}
+
 pub fn create() -> Component {
     Component::new("ActiveMode", Arc::new(render))
 }
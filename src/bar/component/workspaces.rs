@@ -1,16 +1,16 @@
 use super::{Component, ComponentText};
+use crate::display::Display;
+#[cfg(windows)]
 use crate::{
-    display::Display,
-    util,
     workspace::{change_workspace, is_visible_workspace},
     CONFIG, GRIDS, WORKSPACE_ID,
 };
 use std::sync::Arc;
 
+#[cfg(windows)]
 fn render(_: &Component, display: &Display) -> Vec<ComponentText> {
-    let light_theme = CONFIG.lock().unwrap().light_theme;
+    let theme = CONFIG.lock().unwrap().get_theme();
     let workspace_settings = CONFIG.lock().unwrap().workspace_settings.clone();
-    let bar_color = CONFIG.lock().unwrap().bar.color;
     let workspace_id = *WORKSPACE_ID.lock().unwrap();
 
     GRIDS
@@ -22,18 +22,10 @@ fn render(_: &Component, display: &Display) -> Vec<ComponentText> {
                 && g.display.hmonitor == display.hmonitor
         })
         .map(|grid| {
-            let bg = if light_theme {
-                if workspace_id == grid.id {
-                    util::scale_color(bar_color, 0.75) as u32
-                } else {
-                    util::scale_color(bar_color, 0.9) as u32
-                }
+            let bg = if workspace_id == grid.id {
+                theme.active_workspace as u32
             } else {
-                if workspace_id == grid.id {
-                    util::scale_color(bar_color, 2.0) as u32
-                } else {
-                    util::scale_color(bar_color, 1.5) as u32
-                }
+                theme.inactive_workspace as u32
             };
             let mut text = format!(" {} ", grid.id.to_string());
 
@@ -48,6 +40,7 @@ fn render(_: &Component, display: &Display) -> Vec<ComponentText> {
         .collect()
 }
 
+#[cfg(windows)]
 fn on_click(_: &Component, display: &Display, idx: usize) {
     let maybe_id = GRIDS
         .lock()
@@ -66,6 +59,15 @@ fn on_click(_: &Component, display: &Display, idx: usize) {
     }
 }
 
+// there are no workspaces on other platforms, because the window manager doesn't run there
+#[cfg(not(windows))]
+fn render(_: &Component, _: &Display) -> Vec<ComponentText> {
+    Vec::new()
+}
+
+#[cfg(not(windows))]
+fn on_click(_: &Component, _: &Display, _: usize) {}
+
 pub fn create() -> Component {
     Component::new("Workspaces", Arc::new(render))
         .with_on_click(Arc::new(on_click))
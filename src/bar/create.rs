@@ -13,7 +13,7 @@ pub fn create() -> Result<(), util::WinApiResultError> {
 
     let name = "nog_bar";
 
-    let app_bar_bg = CONFIG.lock().unwrap().bar.color;
+    let app_bar_bg = CONFIG.lock().unwrap().get_theme().background;
     let height = CONFIG.lock().unwrap().bar.height;
 
     std::thread::spawn(|| loop {
@@ -30,7 +30,15 @@ pub fn create() -> Result<(), util::WinApiResultError> {
             .expect("Failed to send redraw-app-bar event");
     });
 
-    for display in DISPLAYS.lock().unwrap().clone() {
+    let displays: Vec<_> = DISPLAYS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|d| d.work_mode)
+        .cloned()
+        .collect();
+
+    for display in displays {
         std::thread::spawn(move || unsafe {
             if get_bar_by_hmonitor(display.hmonitor as i32).is_some() {
                 error!(
@@ -42,6 +50,7 @@ pub fn create() -> Result<(), util::WinApiResultError> {
             debug!("Creating appbar for display {}", display.hmonitor as i32);
 
             let working_area_width = display.working_area_width();
+            let height = display.scale(height);
 
             let instance = winapi::um::libloaderapi::GetModuleHandleA(std::ptr::null_mut());
 
@@ -1,5 +1,5 @@
-use super::FONT;
-use crate::CONFIG;
+use super::FONTS;
+use crate::{display::scale_by_dpi, CONFIG};
 use log::debug;
 use std::ffi::CString;
 use winapi::shared::windef::HDC;
@@ -7,21 +7,32 @@ use winapi::um::wingdi::CreateFontIndirectA;
 use winapi::um::wingdi::SelectObject;
 use winapi::um::wingdi::LOGFONTA;
 
-pub fn set_font(dc: HDC) {
+/// Selects the bar font scaled for the given dpi
+pub fn set_font(dc: HDC, dpi: u32) {
+    let font = load_font(dpi);
+
     unsafe {
-        SelectObject(dc, *FONT.lock().unwrap() as *mut std::ffi::c_void);
+        SelectObject(dc, font as *mut std::ffi::c_void);
     }
 }
 
-pub fn load_font() {
-    if *FONT.lock().unwrap() != 0 {
-        return;
+/// Returns the bar font for the given dpi. The font only gets created once per dpi.
+pub fn load_font(dpi: u32) -> i32 {
+    if let Some(font) = FONTS.lock().unwrap().get(&dpi) {
+        return *font;
     }
     unsafe {
         let mut logfont = LOGFONTA::default();
         let mut font_name: [i8; 32] = [0; 32];
-        let app_bar_font = CONFIG.lock().unwrap().bar.font.clone();
-        let app_bar_font_size = CONFIG.lock().unwrap().bar.font_size;
+        let (app_bar_font, app_bar_font_size, dpi_scaling) = {
+            let config = CONFIG.lock().unwrap();
+
+            (
+                config.bar.font.clone(),
+                config.bar.font_size,
+                config.dpi_scaling,
+            )
+        };
 
         for (i, byte) in CString::new(app_bar_font.as_str())
             .unwrap()
@@ -32,13 +43,15 @@ pub fn load_font() {
             font_name[i] = *byte as i8;
         }
 
-        logfont.lfHeight = app_bar_font_size;
+        logfont.lfHeight = scale_by_dpi(app_bar_font_size, dpi, dpi_scaling);
         logfont.lfFaceName = font_name;
 
         let font = CreateFontIndirectA(&logfont) as i32;
 
-        debug!("Using font {}", font);
+        debug!("Using font {} for dpi {}", font, dpi);
+
+        FONTS.lock().unwrap().insert(dpi, font);
 
-        *FONT.lock().unwrap() = font;
+        font
     }
 }
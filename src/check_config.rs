@@ -0,0 +1,107 @@
+use crate::config::{
+    diagnostic::{Diagnostic, Severity},
+    location,
+    rhai::engine::parse_config_file,
+    Config,
+};
+use std::{collections::BTreeMap, path::PathBuf};
+
+fn print_summary(config: &Config) {
+    println!("Applied overrides ({}):", config.applied_overrides.len());
+    for name in &config.applied_overrides {
+        println!("  {}", name);
+    }
+
+    let mut keybindings = BTreeMap::new();
+
+    for kb in &config.keybindings {
+        keybindings
+            .entry(kb.mode.clone().unwrap_or_else(|| "default".into()))
+            .or_insert_with(Vec::new)
+            .push(kb);
+    }
+
+    println!("Keybindings:");
+    for (mode, kbs) in keybindings {
+        println!("  {} ({})", mode, kbs.len());
+        for kb in kbs {
+            println!("    {:?}", kb);
+        }
+    }
+
+    println!("Rules ({}):", config.rules.len());
+    for rule in &config.rules {
+        println!(
+            "  {} manage: {} workspace: {}",
+            rule.matcher, rule.manage, rule.workspace_id
+        );
+    }
+
+    println!("Workspace settings ({}):", config.workspace_settings.len());
+    for setting in &config.workspace_settings {
+        println!(
+            "  {} text: {:?} monitors: {:?}",
+            setting.id, setting.text, setting.monitors
+        );
+    }
+
+    println!("Bar components:");
+    for (section, components) in &[
+        ("left", &config.bar.components.left),
+        ("center", &config.bar.components.center),
+        ("right", &config.bar.components.right),
+    ] {
+        println!(
+            "  {}: {}",
+            section,
+            components
+                .iter()
+                .map(|c| c.name.clone())
+                .collect::<Vec<String>>()
+                .join(", ")
+        );
+    }
+}
+
+fn print_diagnostics(diagnostics: &[Diagnostic]) {
+    for diagnostic in diagnostics {
+        eprintln!("{}", diagnostic);
+    }
+}
+
+/// Evaluates the config without starting the window manager and returns the exit code.
+pub fn run(path: Option<&String>) -> i32 {
+    let path = match path {
+        Some(path) => PathBuf::from(path),
+        None => location::get_config_file(),
+    };
+
+    match parse_config_file(&path) {
+        Ok((config, diagnostics)) => {
+            print_diagnostics(&diagnostics);
+            print_summary(&config);
+
+            let errors = diagnostics
+                .iter()
+                .filter(|d| d.severity == Severity::Error)
+                .count();
+
+            println!(
+                "{} error(s), {} warning(s)",
+                errors,
+                diagnostics.len() - errors
+            );
+
+            if errors > 0 {
+                1
+            } else {
+                0
+            }
+        }
+        Err(diagnostic) => {
+            print_diagnostics(&[diagnostic]);
+            println!("Failed to evaluate the config");
+            1
+        }
+    }
+}
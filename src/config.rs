@@ -1,42 +1,63 @@
 use crate::keybindings::keybinding::Keybinding;
 use bar_config::BarConfig;
-use log::error;
+use monitor_setting::MonitorSetting;
 use rule::Rule;
+use serde::Serialize;
 use std::{collections::HashMap, time::Duration};
+use theme::Theme;
 use update_channel::UpdateChannel;
 use workspace_setting::WorkspaceSetting;
 
 pub mod bar_config;
+pub mod diagnostic;
+#[cfg(windows)]
+pub mod diff;
+pub mod field;
+#[cfg(windows)]
 pub mod hot_reloading;
+pub mod location;
+pub mod monitor_setting;
 pub mod rhai;
 pub mod rule;
+pub mod theme;
 pub mod update_channel;
 pub mod workspace_setting;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct Config {
     pub use_border: bool,
     pub min_width: i32,
     pub min_height: i32,
     pub work_mode: bool,
+    /// Derive the colors of the default theme for a light bar color
     pub light_theme: bool,
+    /// The name of the active theme. The default theme gets derived from the bar color
+    pub active_theme: Option<String>,
+    pub themes: Vec<Theme>,
     pub multi_monitor: bool,
     pub launch_on_startup: bool,
     pub outer_gap: i32,
     pub inner_gap: i32,
+    /// Whether gaps, the bar height and the font size are in logical units that get scaled by the dpi of the display
+    pub dpi_scaling: bool,
     pub remove_title_bar: bool,
     pub remove_task_bar: bool,
     pub display_app_bar: bool,
     pub bar: BarConfig,
     pub workspace_settings: Vec<WorkspaceSetting>,
+    pub monitor_settings: Vec<MonitorSetting>,
     pub keybindings: Vec<Keybinding>,
     pub rules: Vec<Rule>,
+    /// Whether every matching rule gets merged instead of only using the first one
+    pub merge_rules: bool,
     pub update_channels: Vec<UpdateChannel>,
     pub default_update_channel: Option<String>,
     pub update_interval: Duration, //minutes
     /// contains the metadata for each mode (like an icon)
     /// HashMap<mode, (Option<char>)>
     pub mode_meta: HashMap<String, Option<char>>,
+    /// The names of the overrides (e.g. `when_host`) that got merged into the config
+    pub applied_overrides: Vec<String>,
 }
 
 impl Default for Config {
@@ -48,16 +69,22 @@ impl Default for Config {
             use_border: false,
             outer_gap: 0,
             inner_gap: 0,
+            dpi_scaling: true,
             remove_title_bar: false,
             work_mode: true,
             light_theme: false,
+            active_theme: None,
+            themes: Vec::new(),
             multi_monitor: false,
             remove_task_bar: false,
             display_app_bar: false,
             bar: BarConfig::default(),
             mode_meta: HashMap::new(),
+            applied_overrides: Vec::new(),
             workspace_settings: Vec::new(),
+            monitor_settings: Vec::new(),
             keybindings: Vec::new(),
+            merge_rules: false,
             rules: Vec::new(),
             update_channels: Vec::new(),
             default_update_channel: None,
@@ -72,49 +99,31 @@ impl Config {
         Self::default()
     }
 
-    pub fn increment_field(self: &mut Self, field: &str, value: i32) {
-        self.alter_numerical_field(field, value);
+    #[cfg(windows)]
+    pub fn increment_field(&mut self, name: &str, value: i32) -> Result<(), String> {
+        field::find_runtime(name)?.increment(self, value)
     }
 
-    pub fn decrement_field(self: &mut Self, field: &str, value: i32) {
-        self.alter_numerical_field(field, -value);
+    #[cfg(windows)]
+    pub fn decrement_field(&mut self, name: &str, value: i32) -> Result<(), String> {
+        field::find_runtime(name)?.increment(self, -value)
     }
 
-    fn alter_numerical_field(self: &mut Self, field: &str, value: i32) {
-        match field {
-            "bar.height" => self.bar.height += value,
-            "bar.bg" => self.bar.color += value,
-            "bar.font_size" => self.bar.font_size += value,
-            "outer_gap" => self.outer_gap += value,
-            "inner_gap" => self.inner_gap += value,
-            _ => error!("Attempt to alter unknown field: {} by {}", field, value),
-        }
+    #[cfg(windows)]
+    pub fn toggle_field(&mut self, name: &str) -> Result<(), String> {
+        field::find_runtime(name)?.toggle(self)
     }
 
-    pub fn toggle_field(self: &mut Self, field: &str) {
-        match field {
-            "use_border" => self.use_border = !self.use_border,
-            "light_theme" => self.light_theme = !self.light_theme,
-            "launch_on_startup" => self.launch_on_startup = !self.launch_on_startup,
-            "remove_title_bar" => self.remove_title_bar = !self.remove_title_bar,
-            "remove_task_bar" => self.remove_task_bar = !self.remove_task_bar,
-            "display_app_bar" => self.display_app_bar = !self.display_app_bar,
-            _ => error!("Attempt to toggle unknown field: {}", field),
-        }
-    }
-
-    pub fn set_bool_field(self: &mut Self, field: &str, value: bool) {
-        match field {
-            "use_border" => self.use_border = value,
-            "light_theme" => self.light_theme = value,
-            "launch_on_startup" => self.launch_on_startup = value,
-            "remove_title_bar" => self.remove_title_bar = value,
-            "remove_task_bar" => self.remove_task_bar = value,
-            "display_app_bar" => self.display_app_bar = value,
-            _ => error!("Attempt to set unknown field: {}", field),
-        }
+    /// Returns the active theme or the default theme if none is active
+    pub fn get_theme(&self) -> Theme {
+        self.active_theme
+            .as_ref()
+            .and_then(|name| self.themes.iter().find(|t| &t.name == name))
+            .cloned()
+            .unwrap_or_else(|| Theme::derive("default", self.bar.color, self.light_theme))
     }
 
+    #[cfg(windows)]
     pub fn get_update_channel(&self) -> Option<&UpdateChannel> {
         self.default_update_channel
             .clone()
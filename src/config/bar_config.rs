@@ -1,6 +1,7 @@
 use crate::bar::component::{self, Component};
+use serde::Serialize;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct BarComponentsConfig {
     pub left: Vec<Component>,
     pub center: Vec<Component>,
@@ -30,7 +31,7 @@ impl BarComponentsConfig {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize)]
 pub struct BarConfig {
     pub height: i32,
     pub color: i32,
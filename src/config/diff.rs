@@ -0,0 +1,150 @@
+use super::{bar_config::BarComponentsConfig, Config};
+use crate::keybindings::keybinding::Keybinding;
+use std::fmt;
+
+/// The structural difference between two configs
+#[derive(Debug, Default)]
+pub struct ConfigDiff {
+    pub keybindings_added: Vec<Keybinding>,
+    pub keybindings_removed: Vec<Keybinding>,
+    pub rules_changed: bool,
+    pub bar_changed: bool,
+    pub components_changed: bool,
+    pub workspace_settings_changed: bool,
+    pub monitor_settings_changed: bool,
+    pub update_channels_changed: bool,
+    pub themes_changed: bool,
+    /// Names of the changed top level settings (e.g. `inner_gap`)
+    pub changed_fields: Vec<&'static str>,
+}
+
+macro_rules! diff_fields {
+    ($old: ident, $new: ident, $($field: ident),*) => {{
+        let mut fields = Vec::new();
+        $(
+            if $old.$field != $new.$field {
+                fields.push(stringify!($field));
+            }
+        )*
+        fields
+    }};
+}
+
+fn component_names(components: &BarComponentsConfig) -> Vec<Vec<String>> {
+    vec![&components.left, &components.center, &components.right]
+        .into_iter()
+        .map(|section| section.iter().map(|c| c.name.clone()).collect())
+        .collect()
+}
+
+impl ConfigDiff {
+    pub fn new(old: &Config, new: &Config) -> Self {
+        Self {
+            keybindings_added: new
+                .keybindings
+                .iter()
+                .filter(|kb| !old.keybindings.contains(kb))
+                .cloned()
+                .collect(),
+            keybindings_removed: old
+                .keybindings
+                .iter()
+                .filter(|kb| !new.keybindings.contains(kb))
+                .cloned()
+                .collect(),
+            rules_changed: old.rules != new.rules,
+            bar_changed: old.bar != new.bar,
+            components_changed: component_names(&old.bar.components)
+                != component_names(&new.bar.components),
+            workspace_settings_changed: old.workspace_settings != new.workspace_settings,
+            monitor_settings_changed: old.monitor_settings != new.monitor_settings,
+            update_channels_changed: old.update_channels != new.update_channels,
+            themes_changed: old.themes != new.themes,
+            changed_fields: diff_fields!(
+                old,
+                new,
+                use_border,
+                min_width,
+                min_height,
+                work_mode,
+                light_theme,
+                active_theme,
+                multi_monitor,
+                launch_on_startup,
+                outer_gap,
+                inner_gap,
+                dpi_scaling,
+                remove_title_bar,
+                remove_task_bar,
+                display_app_bar,
+                merge_rules,
+                update_interval,
+                default_update_channel,
+                mode_meta
+            ),
+        }
+    }
+
+    pub fn keybindings_changed(&self) -> bool {
+        !self.keybindings_added.is_empty() || !self.keybindings_removed.is_empty()
+    }
+
+    pub fn has_changed(&self, field: &str) -> bool {
+        self.changed_fields.contains(&field)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        !self.keybindings_changed()
+            && !self.rules_changed
+            && !self.bar_changed
+            && !self.components_changed
+            && !self.workspace_settings_changed
+            && !self.monitor_settings_changed
+            && !self.update_channels_changed
+            && !self.themes_changed
+            && self.changed_fields.is_empty()
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_empty() {
+            return f.write_str("nothing changed");
+        }
+
+        let mut changes = Vec::new();
+
+        for kb in &self.keybindings_added {
+            changes.push(format!("added {:?}", kb));
+        }
+        for kb in &self.keybindings_removed {
+            changes.push(format!("removed {:?}", kb));
+        }
+        if self.rules_changed {
+            changes.push("rules".into());
+        }
+        if self.bar_changed {
+            changes.push("bar settings".into());
+        }
+        if self.components_changed {
+            changes.push("bar components".into());
+        }
+        if self.workspace_settings_changed {
+            changes.push("workspace settings".into());
+        }
+        if self.monitor_settings_changed {
+            changes.push("monitor settings".into());
+        }
+        if self.update_channels_changed {
+            changes.push("update channels".into());
+        }
+        if self.themes_changed {
+            changes.push("themes".into());
+        }
+        for field in &self.changed_fields {
+            changes.push(field.to_string());
+        }
+
+        f.write_str(&changes.join(", "))
+    }
+}
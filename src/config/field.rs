@@ -0,0 +1,327 @@
+use super::{diagnostic::closest_match, Config};
+use crate::{event::Event, CHANNEL, CONFIG};
+use lazy_static::lazy_static;
+use rhai::Dynamic;
+use serde::Serialize;
+use std::{fmt, sync::Mutex, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FieldType {
+    Bool,
+    Int,
+    String,
+}
+
+impl fmt::Display for FieldType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            FieldType::Bool => "bool",
+            FieldType::Int => "i32",
+            FieldType::String => "String",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum FieldValue {
+    Bool(bool),
+    Int(i32),
+    String(String),
+}
+
+impl FieldValue {
+    pub fn get_type(&self) -> FieldType {
+        match self {
+            FieldValue::Bool(_) => FieldType::Bool,
+            FieldValue::Int(_) => FieldType::Int,
+            FieldValue::String(_) => FieldType::String,
+        }
+    }
+
+    pub fn from_dynamic(value: &Dynamic) -> Option<Self> {
+        match value.type_name() {
+            "bool" => value.as_bool().ok().map(FieldValue::Bool),
+            "i32" => value.as_int().ok().map(FieldValue::Int),
+            "string" => value
+                .as_str()
+                .ok()
+                .map(|s| FieldValue::String(s.to_string())),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for FieldValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldValue::Bool(value) => write!(f, "{}", value),
+            FieldValue::Int(value) => write!(f, "{}", value),
+            FieldValue::String(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+impl From<FieldValue> for Dynamic {
+    fn from(value: FieldValue) -> Self {
+        match value {
+            FieldValue::Bool(value) => value.into(),
+            FieldValue::Int(value) => value.into(),
+            FieldValue::String(value) => value.into(),
+        }
+    }
+}
+
+/// A setting of the config that can be read and changed by its name.
+///
+/// Changing a field only changes the config. The side effects (e.g. redrawing the bar) get
+/// applied when the changed config is passed to `hot_reload::update_config`.
+pub struct Field {
+    pub name: &'static str,
+    pub typ: FieldType,
+    /// The inclusive range of valid values for numbers
+    pub range: Option<(i32, i32)>,
+    /// Whether changing the field after the config got loaded has an effect. Other fields can
+    /// only be changed while the config gets loaded.
+    pub runtime: bool,
+    get: fn(&Config) -> FieldValue,
+    set: fn(&mut Config, FieldValue),
+}
+
+macro_rules! field {
+    ($name: expr, Bool, $($path: ident).+) => {
+        Field {
+            name: $name,
+            typ: FieldType::Bool,
+            range: None,
+            runtime: true,
+            get: |config| FieldValue::Bool(config.$($path).+),
+            set: |config, value| {
+                if let FieldValue::Bool(value) = value {
+                    config.$($path).+ = value;
+                }
+            },
+        }
+    };
+    ($name: expr, Int, $($path: ident).+, $min: expr, $max: expr) => {
+        Field {
+            name: $name,
+            typ: FieldType::Int,
+            range: Some(($min, $max)),
+            runtime: true,
+            get: |config| FieldValue::Int(config.$($path).+),
+            set: |config, value| {
+                if let FieldValue::Int(value) = value {
+                    config.$($path).+ = value;
+                }
+            },
+        }
+    };
+    ($name: expr, String, $($path: ident).+) => {
+        Field {
+            name: $name,
+            typ: FieldType::String,
+            range: None,
+            runtime: true,
+            get: |config| FieldValue::String(config.$($path).+.clone()),
+            set: |config, value| {
+                if let FieldValue::String(value) = value {
+                    config.$($path).+ = value;
+                }
+            },
+        }
+    };
+}
+
+/// Converts a color between 0xRRGGBB and the 0x00BBGGRR layout windows expects. Swapping the
+/// channels works in both directions.
+pub fn swap_red_and_blue(color: i32) -> i32 {
+    let r = (color >> 16) & 0xff;
+    let g = (color >> 8) & 0xff;
+    let b = color & 0xff;
+
+    (b << 16) | (g << 8) | r
+}
+
+lazy_static! {
+    /// The config before the changes whose side effects haven't been applied yet
+    pub static ref UNAPPLIED_CONFIG: Mutex<Option<Config>> = Mutex::new(None);
+    pub static ref FIELDS: Vec<Field> = vec![
+        field!("use_border", Bool, use_border),
+        // windows only get checked against the minimum size when they appear
+        Field {
+            runtime: false,
+            ..field!("min_width", Int, min_width, 0, 10000)
+        },
+        Field {
+            runtime: false,
+            ..field!("min_height", Int, min_height, 0, 10000)
+        },
+        field!("work_mode", Bool, work_mode),
+        field!("light_theme", Bool, light_theme),
+        field!("multi_monitor", Bool, multi_monitor),
+        field!("launch_on_startup", Bool, launch_on_startup),
+        field!("outer_gap", Int, outer_gap, 0, 1000),
+        field!("inner_gap", Int, inner_gap, 0, 1000),
+        field!("dpi_scaling", Bool, dpi_scaling),
+        field!("remove_title_bar", Bool, remove_title_bar),
+        field!("remove_task_bar", Bool, remove_task_bar),
+        field!("display_app_bar", Bool, display_app_bar),
+        field!("merge_rules", Bool, merge_rules),
+        Field {
+            name: "active_theme",
+            typ: FieldType::String,
+            range: None,
+            runtime: true,
+            get: |config| FieldValue::String(config.active_theme.clone().unwrap_or_default()),
+            set: |config, value| {
+                if let FieldValue::String(name) = value {
+                    config.active_theme = Some(name).filter(|name| !name.is_empty());
+                }
+            },
+        },
+        Field {
+            name: "update_interval",
+            typ: FieldType::Int,
+            range: Some((1, 60 * 24 * 7)),
+            runtime: true,
+            get: |config| FieldValue::Int((config.update_interval.as_secs() / 60) as i32),
+            set: |config, value| {
+                if let FieldValue::Int(minutes) = value {
+                    config.update_interval = Duration::from_secs(minutes as u64 * 60);
+                }
+            },
+        },
+        Field {
+            name: "default_update_channel",
+            typ: FieldType::String,
+            range: None,
+            runtime: true,
+            get: |config| {
+                FieldValue::String(config.default_update_channel.clone().unwrap_or_default())
+            },
+            set: |config, value| {
+                if let FieldValue::String(name) = value {
+                    config.default_update_channel = Some(name).filter(|name| !name.is_empty());
+                }
+            },
+        },
+        field!("bar.height", Int, bar.height, 0, 1000),
+        field!("bar.font", String, bar.font),
+        field!("bar.font_size", Int, bar.font_size, 1, 500),
+        field!("bar.color", Int, bar.color, 0, 0xffffff),
+    ];
+}
+
+/// Returns the names of every field
+pub fn names() -> Vec<&'static str> {
+    FIELDS.iter().map(|field| field.name).collect()
+}
+
+/// Returns the names of the fields that aren't part of a nested config (e.g. the bar)
+pub fn top_level_names() -> Vec<&'static str> {
+    FIELDS
+        .iter()
+        .map(|field| field.name)
+        .filter(|name| !name.contains('.'))
+        .collect()
+}
+
+/// Finds a field that can be changed after the config got loaded
+pub fn find_runtime(name: &str) -> Result<&'static Field, String> {
+    let field = find(name)?;
+
+    if !field.runtime {
+        return Err(format!(
+            "{} can only be changed while the config gets loaded",
+            field.name
+        ));
+    }
+
+    Ok(field)
+}
+
+/// Changes the field of the active config right away. The side effects of the change (e.g.
+/// redrawing the bar) get applied by the main loop after the current script returned.
+pub fn set_at_runtime(field: &Field, value: FieldValue) -> Result<(), String> {
+    let mut unapplied = UNAPPLIED_CONFIG.lock().unwrap();
+    let mut config = CONFIG.lock().unwrap();
+    let previous = config.clone();
+
+    field.set(&mut config, value)?;
+
+    // the effects of every change that happens until then get applied at once
+    if unapplied.is_none() {
+        *unapplied = Some(previous);
+
+        let _ = CHANNEL.sender.clone().send(Event::ConfigChanged);
+    }
+
+    Ok(())
+}
+
+pub fn find(name: &str) -> Result<&'static Field, String> {
+    // bar.bg is the name that was used for the color of the bar before
+    let name = if name == "bar.bg" { "bar.color" } else { name };
+
+    FIELDS
+        .iter()
+        .find(|field| field.name == name)
+        .ok_or_else(|| match closest_match(name, &names()) {
+            Some(suggestion) => format!("unknown field {} (did you mean {}?)", name, suggestion),
+            None => format!("unknown field {}", name),
+        })
+}
+
+impl Field {
+    pub fn get(&self, config: &Config) -> FieldValue {
+        (self.get)(config)
+    }
+
+    /// Changes the field if the value has the right type and is in the range of the field
+    pub fn set(&self, config: &mut Config, value: FieldValue) -> Result<(), String> {
+        if value.get_type() != self.typ {
+            return Err(format!(
+                "{} expects a value of type {}, found {}",
+                self.name,
+                self.typ,
+                value.get_type()
+            ));
+        }
+
+        if let (FieldValue::Int(value), Some((min, max))) = (&value, self.range) {
+            if *value < min || *value > max {
+                return Err(format!(
+                    "{} has to be between {} and {}, found {}",
+                    self.name, min, max, value
+                ));
+            }
+        }
+
+        (self.set)(config, value);
+
+        Ok(())
+    }
+
+    /// Adds the amount to the field. The result gets clamped to the range of the field.
+    #[cfg(windows)]
+    pub fn increment(&self, config: &mut Config, amount: i32) -> Result<(), String> {
+        match self.get(config) {
+            FieldValue::Int(value) => {
+                let (min, max) = self.range.unwrap_or((i32::MIN, i32::MAX));
+                let value = value.saturating_add(amount).max(min).min(max);
+
+                self.set(config, FieldValue::Int(value))
+            }
+            _ => Err(format!("{} is not a number", self.name)),
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn toggle(&self, config: &mut Config) -> Result<(), String> {
+        match self.get(config) {
+            FieldValue::Bool(value) => self.set(config, FieldValue::Bool(!value)),
+            _ => Err(format!("{} is not a boolean", self.name)),
+        }
+    }
+}
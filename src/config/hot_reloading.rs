@@ -1,3 +1,4 @@
+use crate::config::rhai::engine::CONFIG_FILES;
 use crate::event::Event;
 use crate::CHANNEL;
 use log::{debug, error};
@@ -5,7 +6,47 @@ use notify::watcher;
 use notify::DebouncedEvent;
 use notify::RecursiveMode;
 use notify::Watcher;
-use std::sync::mpsc::channel;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+/// How long to wait for further changes before reloading. Editors often save through a temp file
+/// followed by a rename, which results in a burst of events. Only changes to config files extend
+/// the wait, so unrelated files in the same directory (e.g. logs) can't delay the reload.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+fn normalize(path: &Path) -> PathBuf {
+    std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn get_config_files() -> HashSet<PathBuf> {
+    CONFIG_FILES
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|p| normalize(p))
+        .collect()
+}
+
+/// The directories get watched instead of the files themselves, because a file that gets replaced
+/// through a rename wouldn't be watched anymore.
+fn get_config_dirs(files: &HashSet<PathBuf>) -> HashSet<PathBuf> {
+    files
+        .iter()
+        .filter_map(|p| p.parent())
+        .map(|p| p.to_path_buf())
+        .collect()
+}
+
+fn is_config_change(ev: &DebouncedEvent, files: &HashSet<PathBuf>) -> bool {
+    match ev {
+        DebouncedEvent::Write(path)
+        | DebouncedEvent::Create(path)
+        | DebouncedEvent::Rename(_, path) => files.contains(&normalize(path)),
+        _ => false,
+    }
+}
 
 pub fn start() {
     std::thread::spawn(|| {
@@ -14,30 +55,55 @@ pub fn start() {
         let mut watcher = watcher(tx, std::time::Duration::from_millis(10))
             .expect("Failed to spawn file watcher");
 
-        let mut path = dirs::config_dir().expect("Failed to get config dir");
+        let mut files = HashSet::new();
+        let mut dirs = HashSet::new();
+        // when the config should get reloaded, if there are no further changes until then
+        let mut deadline: Option<Instant> = None;
 
-        path.push("nog");
-        path.push("config.nog");
+        loop {
+            let new_files = get_config_files();
 
-        watcher
-            .watch(path, RecursiveMode::NonRecursive)
-            .expect("Failed to watch config directory");
+            if new_files != files {
+                let new_dirs = get_config_dirs(&new_files);
 
-        loop {
-            match rx.recv() {
-                Ok(ev) => match ev {
-                    DebouncedEvent::Write(_) => {
-                        debug!("detected config change");
-                        CHANNEL
-                            .sender
-                            .clone()
-                            .send(Event::ReloadConfig)
-                            .expect("Failed to send ReloadConfig event");
+                for dir in dirs.difference(&new_dirs) {
+                    let _ = watcher.unwatch(dir);
+                }
+
+                for dir in new_dirs.difference(&dirs) {
+                    debug!("Watching {:?}", dir);
+                    if let Err(e) = watcher.watch(dir, RecursiveMode::NonRecursive) {
+                        error!("Failed to watch {:?}: {:?}", dir, e);
                     }
-                    _ => {}
-                },
+                }
+
+                files = new_files;
+                dirs = new_dirs;
+            }
+
+            let timeout = deadline
+                .map(|d| d.saturating_duration_since(Instant::now()))
+                .unwrap_or(DEBOUNCE);
+
+            match rx.recv_timeout(timeout) {
+                Ok(ev) => {
+                    if is_config_change(&ev, &files) {
+                        debug!("detected config change {:?}", ev);
+                        deadline = Some(Instant::now() + DEBOUNCE);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {}
                 Err(e) => error!("watch error: {:?}", e),
             }
+
+            if deadline.map_or(false, |d| Instant::now() >= d) {
+                deadline = None;
+                CHANNEL
+                    .sender
+                    .clone()
+                    .send(Event::ReloadConfig)
+                    .expect("Failed to send ReloadConfig event");
+            }
         }
     });
 }
@@ -0,0 +1,135 @@
+use lazy_static::lazy_static;
+use std::{path::PathBuf, sync::Mutex};
+
+lazy_static! {
+    static ref LOCATION: Mutex<Location> = Mutex::new(Location::default());
+}
+
+/// Where the config gets loaded from
+#[derive(Debug, Clone, Default)]
+struct Location {
+    /// Either the config folder or the entry file, set through `--config` or `NOG_CONFIG`
+    path: Option<PathBuf>,
+    /// The active profile, set through `--profile`, `NOG_PROFILE` or at runtime
+    profile: Option<String>,
+}
+
+/// Reads the location from the environment and the cli flags and returns the remaining arguments.
+///
+/// The flags take precedence over the environment variables.
+pub fn init(args: Vec<String>) -> Vec<String> {
+    let mut location = Location {
+        path: std::env::var("NOG_CONFIG").ok().map(PathBuf::from),
+        profile: std::env::var("NOG_PROFILE").ok(),
+    };
+    let mut rest = Vec::new();
+    let mut args = args.into_iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => location.path = args.next().map(PathBuf::from),
+            "--profile" => location.profile = args.next(),
+            _ => rest.push(arg),
+        }
+    }
+
+    if let Some(Err(e)) = location.profile.as_deref().map(check_profile) {
+        eprintln!("{}. Using the default config file instead", e);
+        location.profile = None;
+    }
+
+    *LOCATION.lock().unwrap() = location;
+
+    rest
+}
+
+/// Whether the location got set through `--config` or `NOG_CONFIG`
+#[cfg(windows)]
+pub fn is_custom() -> bool {
+    LOCATION.lock().unwrap().path.is_some()
+}
+
+/// Returns the folder that contains the config, the logs and the profiles
+pub fn get_config_dir() -> PathBuf {
+    match LOCATION.lock().unwrap().path.clone() {
+        Some(path) if path.extension().map(|e| e == "nog").unwrap_or(false) => {
+            path.parent().map(|p| p.to_path_buf()).unwrap_or_default()
+        }
+        Some(path) => path,
+        None => {
+            let mut path = dirs::config_dir().unwrap_or_default();
+            path.push("nog");
+            path
+        }
+    }
+}
+
+/// Returns the entry file of the active profile.
+///
+/// A profile called `work` lives in `work.nog` next to the default `config.nog`.
+pub fn get_config_file() -> PathBuf {
+    let location = LOCATION.lock().unwrap().clone();
+
+    match location.profile {
+        Some(profile) => {
+            let mut path = get_config_dir();
+            path.push(format!("{}.nog", profile));
+            path
+        }
+        None => match location.path {
+            Some(path) if path.extension().map(|e| e == "nog").unwrap_or(false) => path,
+            _ => {
+                let mut path = get_config_dir();
+                path.push("config.nog");
+                path
+            }
+        },
+    }
+}
+
+pub fn get_profile() -> Option<String> {
+    LOCATION.lock().unwrap().profile.clone()
+}
+
+/// Makes sure that the profile is the name of a file in the config folder and not a path
+/// that points somewhere else
+pub fn check_profile(profile: &str) -> Result<(), String> {
+    if profile.is_empty() || profile.contains(&['/', '\\', ':'][..]) || profile.contains("..") {
+        return Err(format!("{:?} is not a valid profile name", profile));
+    }
+
+    Ok(())
+}
+
+/// Changes the active profile. `default` selects the default config file.
+#[cfg(windows)]
+pub fn set_profile(profile: &str) -> Result<(), String> {
+    check_profile(profile)?;
+
+    LOCATION.lock().unwrap().profile = if profile == "default" {
+        None
+    } else {
+        Some(profile.to_string())
+    };
+
+    Ok(())
+}
+
+/// Returns the cli flags that are needed to start nog with the same location again
+#[cfg(windows)]
+pub fn get_args() -> Vec<String> {
+    let location = LOCATION.lock().unwrap().clone();
+    let mut args = Vec::new();
+
+    if let Some(path) = location.path {
+        args.push("--config".into());
+        args.push(path.to_string_lossy().to_string());
+    }
+
+    if let Some(profile) = location.profile {
+        args.push("--profile".into());
+        args.push(profile);
+    }
+
+    args
+}
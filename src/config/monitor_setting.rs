@@ -0,0 +1,18 @@
+use super::workspace_setting::monitor_selector::MonitorSelector;
+use serde::Serialize;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MonitorSetting {
+    pub monitor: MonitorSelector,
+    /// Whether nog manages windows on this monitor
+    pub work_mode: bool,
+}
+
+impl Default for MonitorSetting {
+    fn default() -> Self {
+        Self {
+            monitor: MonitorSelector::Primary,
+            work_mode: true,
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use crate::config::diagnostic::{closest_match, Diagnostic, Severity};
+use lazy_static::lazy_static;
+use rhai::{Dynamic, Map, Position};
+use std::sync::Mutex;
+
+lazy_static! {
+    static ref DIAGNOSTICS: Mutex<Vec<Diagnostic>> = Mutex::new(Vec::new());
+    /// Stack of the files that are currently getting evaluated
+    static ref FILES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+}
+
+pub fn push_file(file: String) {
+    FILES.lock().unwrap().push(file);
+}
+
+pub fn pop_file() {
+    FILES.lock().unwrap().pop();
+}
+
+pub fn current_file() -> Option<String> {
+    FILES.lock().unwrap().last().cloned()
+}
+
+pub fn clear() {
+    DIAGNOSTICS.lock().unwrap().clear();
+    FILES.lock().unwrap().clear();
+}
+
+/// Returns every diagnostic reported since the last call
+pub fn take() -> Vec<Diagnostic> {
+    std::mem::replace(&mut *DIAGNOSTICS.lock().unwrap(), Vec::new())
+}
+
+pub fn report(severity: Severity, message: String, position: Position, suggestion: Option<String>) {
+    let mut diagnostic = Diagnostic::new(severity, message, position);
+
+    diagnostic.file = current_file();
+    diagnostic.suggestion = suggestion;
+
+    DIAGNOSTICS.lock().unwrap().push(diagnostic);
+}
+
+pub fn error(message: String, position: Position, suggestion: Option<String>) {
+    report(Severity::Error, message, position, suggestion);
+}
+
+pub fn type_error(name: &str, expected: &str, value: &Dynamic, position: Position) {
+    error(
+        format!(
+            "{} has to be of type {} not {}",
+            name,
+            expected,
+            value.type_name()
+        ),
+        position,
+        Some(format!("change the value to a {}", expected)),
+    );
+}
+
+pub fn unknown_key(key: &str, known_keys: &[&str], position: Position) {
+    report(
+        Severity::Warning,
+        format!("unknown key {}", key),
+        position,
+        closest_match(key, known_keys).map(|k| format!("did you mean {}?", k)),
+    );
+}
+
+/// Reports every key of the map that isn't one of the known keys
+pub fn check_keys(map: &Map, known_keys: &[&str], position: Position) {
+    for key in map.keys() {
+        if !known_keys.contains(&key.as_str()) {
+            unknown_key(key, known_keys, position);
+        }
+    }
+}
@@ -1,17 +1,29 @@
-use super::{functions, lib, modules, syntax, types};
-use crate::config::Config;
+use super::{
+    diagnostics, functions, hooks, lib, modules, overrides,
+    resolver::{self, FileTrackingResolver},
+    syntax, timers, types,
+};
+use crate::config::{
+    diagnostic::{Diagnostic, Severity},
+    location, Config,
+};
 use lazy_static::lazy_static;
-use log::{debug, error};
+use log::debug;
+#[cfg(windows)]
+use log::error;
+#[cfg(windows)]
+use rhai::Dynamic;
 use rhai::{
-    module_resolvers::{FileModuleResolver, ModuleResolversCollection},
-    Engine, FnPtr, Scope,
+    module_resolvers::ModuleResolversCollection, Engine, EvalAltResult, FnPtr, Position, Scope,
 };
 use std::{
     io::Write,
     path::PathBuf,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
 };
-use winapi::um::wingdi::{GetBValue, GetGValue, GetRValue, RGB};
 
 lazy_static! {
     pub static ref MODE: Mutex<Option<String>> = Mutex::new(None);
@@ -19,29 +31,104 @@ lazy_static! {
     pub static ref SCOPE: Mutex<Scope<'static>> = Mutex::new(Scope::new());
     pub static ref AST: Mutex<rhai::AST> = Mutex::new(rhai::AST::default());
     pub static ref CALLBACKS: Mutex<Vec<FnPtr>> = Mutex::new(Vec::new());
+    /// The callbacks of the config that is being evaluated. They replace CALLBACKS once the
+    /// evaluation succeeded, so a broken config doesn't invalidate the indices of the active one.
+    static ref PENDING_CALLBACKS: Mutex<Vec<FnPtr>> = Mutex::new(Vec::new());
+    /// Every file that got evaluated during the last successful parse
+    pub static ref CONFIG_FILES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+    /// Whether a config is currently being evaluated
+    static ref EVALUATING: AtomicBool = AtomicBool::new(false);
+}
+
+/// Returns true while the config gets evaluated, where the config that is being built has to be
+/// used instead of the active one.
+pub fn is_evaluating() -> bool {
+    EVALUATING.load(Ordering::SeqCst)
 }
 
 pub fn add_callback(fp: FnPtr) -> usize {
-    let mut callbacks = CALLBACKS.lock().unwrap();
+    let callbacks = if is_evaluating() {
+        &*PENDING_CALLBACKS
+    } else {
+        &*CALLBACKS
+    };
+    let mut callbacks = callbacks.lock().unwrap();
     let idx = callbacks.len();
     callbacks.push(fp);
     idx
 }
 
+#[cfg(windows)]
 pub fn call(idx: usize) {
+    // the callback can register new callbacks, so CALLBACKS must not be locked while it runs
+    let fp = CALLBACKS.lock().unwrap()[idx].clone();
+
+    call_fn_ptr(fp, Vec::new());
+}
+
+/// Calls the function directly instead of looking it up in CALLBACKS.
+///
+/// If the function doesn't exist anymore (e.g. because the config got reloaded) the error
+/// gets logged.
+#[cfg(windows)]
+pub fn call_fn_ptr(fp: FnPtr, args: Vec<Dynamic>) {
     let engine = ENGINE.lock().unwrap();
     let ast = AST.lock().unwrap();
-    let callbacks = CALLBACKS.lock().unwrap();
-    let _ = callbacks[idx]
-        .call_dynamic(&*engine, &*ast, None, [])
+    let _ = fp
+        .call_dynamic(&*engine, &*ast, None, args)
         .map_err(|e| error!("{}", e.to_string()));
 }
 
-fn build_relative_resolver(config_path: &PathBuf) -> FileModuleResolver {
-    FileModuleResolver::new_with_path_and_extension(config_path.clone(), "nog")
+fn build_relative_resolver(config_path: &PathBuf) -> FileTrackingResolver {
+    FileTrackingResolver::new(config_path.clone())
+}
+
+fn to_diagnostic(error: Box<EvalAltResult>, file: &PathBuf) -> Diagnostic {
+    let mut diagnostic = Diagnostic::new(Severity::Error, error.to_string(), error.position());
+
+    diagnostic.file = Some(file.to_string_lossy().to_string());
+
+    diagnostic
+}
+
+fn io_diagnostic(error: std::io::Error, file: &PathBuf) -> Diagnostic {
+    let mut diagnostic = Diagnostic::new(Severity::Error, error.to_string(), Position::none());
+
+    diagnostic.file = Some(file.to_string_lossy().to_string());
+
+    diagnostic
+}
+
+/// Evaluates the entry file of the active profile. The default config file gets created
+/// with the default config if it doesn't exist yet.
+///
+/// Problems that only affect a single statement get skipped and returned as diagnostics,
+/// while errors that stop the evaluation of the config are returned as `Err`.
+pub fn parse_config() -> Result<(Config, Vec<Diagnostic>), Diagnostic> {
+    let config_dir = location::get_config_dir();
+    let config_path = location::get_config_file();
+
+    if !config_dir.exists() {
+        debug!("nog folder doesn't exist yet. Creating the folder");
+        std::fs::create_dir_all(config_dir.clone()).map_err(|e| io_diagnostic(e, &config_dir))?;
+    }
+
+    if !config_path.exists() && location::get_profile().is_none() {
+        debug!("config file doesn't exist yet. Creating the file");
+        if let Ok(mut file) = std::fs::File::create(config_path.clone()) {
+            debug!("Initializing config with default values");
+            file.write_all(include_bytes!("../../../assets/default_config.nog"))
+                .map_err(|e| io_diagnostic(e, &config_path))?;
+        }
+    }
+
+    parse_config_file(&config_path)
 }
 
-pub fn parse_config() -> Result<Config, String> {
+/// Evaluates the given config file. Modules get resolved relative to the folder of the file.
+///
+/// This doesn't depend on any state of the window manager, so it can also be used to check a config.
+pub fn parse_config_file(config_path: &PathBuf) -> Result<(Config, Vec<Diagnostic>), Diagnostic> {
     let mut engine = Engine::new();
     let mut scope = Scope::new();
     let mut config = Arc::new(Mutex::new(Config::default()));
@@ -50,62 +137,79 @@ pub fn parse_config() -> Result<Config, String> {
     types::init(&mut engine);
     functions::init(&mut engine);
     lib::init(&mut engine);
+    hooks::init(&mut engine).unwrap();
+    timers::init(&mut engine);
 
-    *CALLBACKS.lock().unwrap() = Vec::new();
+    PENDING_CALLBACKS.lock().unwrap().clear();
+    diagnostics::clear();
+    overrides::clear();
+    resolver::take_resolved_files();
 
     let mut resolver_collection = ModuleResolversCollection::new();
 
     let modules_resolver = modules::new();
     resolver_collection.push(modules_resolver);
 
-    let mut config_path: PathBuf = dirs::config_dir().unwrap_or_default();
-
-    config_path.push("nog");
+    let config_dir = config_path
+        .parent()
+        .map(|p| p.to_path_buf())
+        .unwrap_or_default();
 
-    let relative_resolver = build_relative_resolver(&config_path);
+    let relative_resolver = build_relative_resolver(&config_dir);
 
     resolver_collection.push(relative_resolver);
 
     engine.set_module_resolver(Some(resolver_collection));
     engine.set_max_expr_depths(0, 0);
 
-    if !config_path.exists() {
-        debug!("nog folder doesn't exist yet. Creating the folder");
-        std::fs::create_dir(config_path.clone()).map_err(|e| e.to_string())?;
-    }
+    diagnostics::push_file(config_path.to_string_lossy().to_string());
 
-    config_path.push("config.nog");
+    EVALUATING.store(true, Ordering::SeqCst);
 
-    if !config_path.exists() {
-        debug!("config file doesn't exist yet. Creating the file");
-        if let Ok(mut file) = std::fs::File::create(config_path.clone()) {
-            debug!("Initializing config with default values");
-            file.write_all(include_bytes!("../../../assets/default_config.nog"))
-                .map_err(|e| e.to_string())?;
+    debug!("Parsing config file");
+    let result = engine
+        .compile_file_with_scope(&scope, config_path.clone())
+        .and_then(|ast| {
+            debug!("Running config file");
+            engine.consume_ast_with_scope(&mut scope, &ast).map(|_| ast)
+        });
+
+    EVALUATING.store(false, Ordering::SeqCst);
+
+    let ast = result.map_err(|e| to_diagnostic(e, config_path))?;
+
+    {
+        let mut config = config.lock().unwrap();
+
+        let applied_overrides = overrides::apply(&mut config);
+        config.applied_overrides = applied_overrides;
+
+        if let Some(name) = &config.active_theme {
+            if config.themes.iter().all(|t| &t.name != name) {
+                diagnostics::report(
+                    Severity::Warning,
+                    format!("unknown theme {}, using the default colors", name),
+                    Position::none(),
+                    Some("declare the theme before activating it".into()),
+                );
+            }
         }
     }
 
-    debug!("Parsing config file");
-    let ast = engine
-        .compile_file_with_scope(&scope, config_path)
-        .map_err(|e| e.to_string())?;
-
-    debug!("Running config file");
-    engine
-        .consume_ast_with_scope(&mut scope, &ast)
-        .map_err(|e| e.to_string())?;
+    diagnostics::pop_file();
 
     *ENGINE.lock().unwrap() = engine;
     *SCOPE.lock().unwrap() = scope;
     *AST.lock().unwrap() = ast;
+    *CALLBACKS.lock().unwrap() = std::mem::take(&mut *PENDING_CALLBACKS.lock().unwrap());
+    hooks::commit();
+    timers::commit();
 
-    let mut config = config.lock().unwrap().clone();
+    let mut files = vec![config_path.clone()];
+    files.extend(resolver::take_resolved_files());
+    *CONFIG_FILES.lock().unwrap() = files;
 
-    config.bar.color = RGB(
-        GetBValue(config.bar.color as u32),
-        GetGValue(config.bar.color as u32),
-        GetRValue(config.bar.color as u32),
-    ) as i32;
+    let config = config.lock().unwrap().clone();
 
-    Ok(config)
+    Ok((config, diagnostics::take()))
 }
@@ -1,12 +1,92 @@
-use super::engine;
+use super::{diagnostics, engine};
 use crate::{
-    direction::Direction, keybindings::keybinding_type::KeybindingType,
+    config::field::{self, FieldType},
+    direction::Direction,
+    event::Event,
+    keybindings::{
+        condition::{Condition, ConditionalAction},
+        keybinding_type::KeybindingType,
+    },
     split_direction::SplitDirection,
+    CHANNEL,
+};
+use regex::Regex;
+use rhai::{
+    Array, Dynamic, Engine, EvalAltResult, FnPtr, Map, Position, RegisterFn, RegisterResultFn,
 };
-use rhai::{Engine, FnPtr, RegisterFn};
 use std::str::FromStr;
 
+const CONDITION_KEYS: &[&str] = &["process", "title", "class", "managed", "floating"];
+
+fn to_condition(settings: Map) -> Result<Condition, String> {
+    let mut condition = Condition::default();
+
+    diagnostics::check_keys(&settings, CONDITION_KEYS, Position::none());
+
+    for (key, value) in settings {
+        let flag = match key.as_str() {
+            "managed" => Some(&mut condition.managed),
+            "floating" => Some(&mut condition.floating),
+            _ => None,
+        };
+
+        if let Some(flag) = flag {
+            let value = value
+                .as_bool()
+                .map_err(|_| format!("{} has to be a boolean", key))?;
+
+            *flag = Some(value);
+            continue;
+        }
+
+        let pattern = match key.as_str() {
+            "process" => &mut condition.process,
+            "title" => &mut condition.title,
+            "class" => &mut condition.class,
+            _ => continue,
+        };
+
+        let regex = Regex::new(&format!("^{}$", value))
+            .map_err(|e| format!("{} is not a valid pattern: {}", value, e))?;
+
+        *pattern = Some(regex);
+    }
+
+    Ok(condition)
+}
+
+/// Creates the action if the field exists and has the expected type.
+///
+/// While the config gets evaluated a problem gets reported and the keybinding gets skipped,
+/// afterwards (e.g. in a callback) it is a runtime error.
+fn check_field(
+    name: &str,
+    typ: FieldType,
+    action: impl FnOnce() -> KeybindingType,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    let error = match field::find_runtime(name) {
+        Ok(field) if field.typ == typ => return Ok(Dynamic::from(action())),
+        Ok(field) => format!("{} has to be of type {} not {}", name, typ, field.typ),
+        Err(e) => e,
+    };
+
+    if !engine::is_evaluating() {
+        return Err(EvalAltResult::ErrorRuntime(error, Position::none()).into());
+    }
+
+    match field::find(name) {
+        Ok(_) => diagnostics::error(error, Position::none(), None),
+        Err(_) => diagnostics::unknown_key(name, &field::names(), Position::none()),
+    }
+
+    Ok(().into())
+}
+
 pub fn init(engine: &mut Engine) {
+    engine
+        .register_custom_operator("when", 10)
+        .expect("Failed to register the when operator");
+
     engine.register_fn("callback", |fp: FnPtr| {
         KeybindingType::Callback(engine::add_callback(fp))
     });
@@ -20,7 +100,17 @@ pub fn init(engine: &mut Engine) {
         KeybindingType::ToggleFloatingMode
     });
     engine.register_fn("toggle_work_mode", || KeybindingType::ToggleWorkMode);
+    engine.register_fn("toggle_monitor_work_mode", || {
+        KeybindingType::ToggleMonitorWorkMode
+    });
     engine.register_fn("toggle_fullscreen", || KeybindingType::ToggleFullscreen);
+    engine.register_fn("explain_window", || KeybindingType::ExplainWindow);
+    engine.register_fn("switch_profile", |profile: String| {
+        KeybindingType::SwitchProfile(profile)
+    });
+    engine.register_fn("switch_theme", |name: String| {
+        KeybindingType::SwitchTheme(name)
+    });
     engine.register_fn("change_workspace", |id: i32| {
         KeybindingType::ChangeWorkspace(id)
     });
@@ -33,14 +123,20 @@ pub fn init(engine: &mut Engine) {
     engine.register_fn("toggle_mode", |mode: String| {
         KeybindingType::ToggleMode(mode)
     });
-    engine.register_fn("increment_config", |key: String, value: i32| {
-        KeybindingType::IncrementConfig(key, value)
+    engine.register_result_fn("increment_config", |key: String, value: i32| {
+        check_field(&key, FieldType::Int, || {
+            KeybindingType::IncrementConfig(key.clone(), value)
+        })
     });
-    engine.register_fn("decrement_config", |key: String, value: i32| {
-        KeybindingType::DecrementConfig(key, value)
+    engine.register_result_fn("decrement_config", |key: String, value: i32| {
+        check_field(&key, FieldType::Int, || {
+            KeybindingType::DecrementConfig(key.clone(), value)
+        })
     });
-    engine.register_fn("toggle_config", |key: String| {
-        KeybindingType::ToggleConfig(key)
+    engine.register_result_fn("toggle_config", |key: String| {
+        check_field(&key, FieldType::Bool, || {
+            KeybindingType::ToggleConfig(key.clone())
+        })
     });
     engine.register_fn("launch", |program: String| KeybindingType::Launch(program));
     engine.register_fn("focus", |direction: String| {
@@ -55,4 +151,51 @@ pub fn init(engine: &mut Engine) {
     engine.register_fn("split", |direction: String| {
         KeybindingType::Split(SplitDirection::from_str(&direction).unwrap())
     });
+    engine.register_result_fn("chain", |actions: Array| {
+        let mut sequence = Vec::new();
+
+        for (i, action) in actions.into_iter().enumerate() {
+            match action.clone().try_cast::<KeybindingType>() {
+                Some(action) => sequence.push(action),
+                // only the element gets skipped, so the rest of the chain still works
+                None if engine::is_evaluating() => diagnostics::type_error(
+                    &format!("element {} of chain", i + 1),
+                    "KeybindingType",
+                    &action,
+                    Position::none(),
+                ),
+                None => {
+                    return Err(EvalAltResult::ErrorRuntime(
+                        format!(
+                            "chain expects an array of actions, found {}",
+                            action.type_name()
+                        ),
+                        Position::none(),
+                    )
+                    .into())
+                }
+            }
+        }
+
+        Ok(Dynamic::from(KeybindingType::Sequence(sequence)))
+    });
+    engine.register_result_fn("when", |action: KeybindingType, settings: Map| {
+        match to_condition(settings) {
+            Ok(condition) => Ok(Dynamic::from(ConditionalAction {
+                typ: action,
+                condition,
+            })),
+            // the whole binding gets dropped, because without its condition it would apply to
+            // every window
+            Err(e) if engine::is_evaluating() => {
+                diagnostics::error(e, Position::none(), None);
+                Ok(().into())
+            }
+            Err(e) => Err(EvalAltResult::ErrorRuntime(e, Position::none()).into()),
+        }
+    });
+    // the action gets executed by the main loop after the current callback returned
+    engine.register_fn("dispatch", |action: KeybindingType| {
+        let _ = CHANNEL.sender.clone().send(Event::Action(action));
+    });
 }
@@ -0,0 +1,170 @@
+use super::{diagnostics, engine};
+use crate::config::diagnostic::closest_match;
+#[cfg(windows)]
+use crate::{event::Event, window::Window, CHANNEL};
+use lazy_static::lazy_static;
+#[cfg(windows)]
+use log::error;
+#[cfg(windows)]
+use rhai::Map;
+use rhai::{Dynamic, Engine, EvalAltResult, FnPtr, ImmutableString, ParseError, Position};
+use std::{collections::HashMap, sync::Mutex};
+
+pub const EVENTS: &[&str] = &[
+    "workspace_changed",
+    "window_managed",
+    "focus_changed",
+    "mode_changed",
+    "config_reloaded",
+];
+
+lazy_static! {
+    /// Maps the name of an event to the callbacks that subscribed to it. The callbacks aren't
+    /// stored in CALLBACKS, so hooks that get added at runtime don't grow it.
+    static ref HOOKS: Mutex<HashMap<String, Vec<FnPtr>>> = Mutex::new(HashMap::new());
+    /// The hooks of the config that is being evaluated
+    static ref PENDING_HOOKS: Mutex<HashMap<String, Vec<FnPtr>>> = Mutex::new(HashMap::new());
+}
+
+/// Reports the problem while the config gets evaluated, so only the call gets skipped. Calls
+/// that happen later (e.g. in a callback) fail with a runtime error instead.
+fn invalid_call(
+    message: String,
+    position: Position,
+    suggestion: Option<String>,
+) -> Result<Dynamic, Box<EvalAltResult>> {
+    if engine::is_evaluating() {
+        diagnostics::error(message, position, suggestion);
+        return Ok(().into());
+    }
+
+    let message = match suggestion {
+        Some(suggestion) => format!("{} ({})", message, suggestion),
+        None => message,
+    };
+
+    Err(EvalAltResult::ErrorRuntime(message, position).into())
+}
+
+pub fn init(engine: &mut Engine) -> Result<(), ParseError> {
+    PENDING_HOOKS.lock().unwrap().clear();
+
+    // a custom syntax instead of a function, because functions don't know the position of
+    // the call
+    engine.register_custom_syntax(
+        &["on", "(", "$expr$", ",", "$expr$", ")"], // the custom syntax
+        0, // the number of new variables declared within this custom syntax
+        |engine, ctx, scope, inputs| {
+            let event_expr = inputs.get(0).unwrap();
+            let callback_expr = inputs.get(1).unwrap();
+            let event = engine.eval_expression_tree(ctx, scope, event_expr)?;
+            let callback = engine.eval_expression_tree(ctx, scope, callback_expr)?;
+
+            let event = match event.clone().try_cast::<ImmutableString>() {
+                Some(event) => event,
+                None => {
+                    return invalid_call(
+                        format!(
+                            "argument 1 has to be of type String not {}",
+                            event.type_name()
+                        ),
+                        event_expr.position(),
+                        None,
+                    )
+                }
+            };
+
+            if !EVENTS.contains(&event.as_str()) {
+                return invalid_call(
+                    format!("unknown event {}", event),
+                    event_expr.position(),
+                    closest_match(&event, EVENTS).map(|e| format!("did you mean {}?", e)),
+                );
+            }
+
+            let fp = match callback.clone().try_cast::<FnPtr>() {
+                Some(fp) => fp,
+                None => {
+                    return invalid_call(
+                        format!(
+                            "argument 2 has to be of type FnPtr not {}",
+                            callback.type_name()
+                        ),
+                        callback_expr.position(),
+                        None,
+                    )
+                }
+            };
+
+            let hooks = if engine::is_evaluating() {
+                &*PENDING_HOOKS
+            } else {
+                &*HOOKS
+            };
+
+            hooks
+                .lock()
+                .unwrap()
+                .entry(event.to_string())
+                .or_insert_with(Vec::new)
+                .push(fp);
+
+            Ok(().into())
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Replaces the hooks with the ones of the config that just got evaluated
+pub fn commit() {
+    *HOOKS.lock().unwrap() = std::mem::take(&mut *PENDING_HOOKS.lock().unwrap());
+}
+
+/// Queues the event, so the callbacks get called by the main loop.
+///
+/// This way the callbacks never run while one of the globals is locked.
+#[cfg(windows)]
+pub fn emit(event: &str, args: Vec<Dynamic>) {
+    let has_hooks = HOOKS
+        .lock()
+        .unwrap()
+        .get(event)
+        .map(|hooks| !hooks.is_empty())
+        .unwrap_or(false);
+
+    if has_hooks {
+        let _ = CHANNEL
+            .sender
+            .clone()
+            .send(Event::Hook(event.to_string(), args))
+            .map_err(|e| error!("{:?}", e));
+    }
+}
+
+/// Calls every callback that subscribed to the event
+#[cfg(windows)]
+pub fn dispatch(event: &str, args: Vec<Dynamic>) {
+    let hooks = HOOKS
+        .lock()
+        .unwrap()
+        .get(event)
+        .cloned()
+        .unwrap_or_default();
+
+    for fp in hooks {
+        engine::call_fn_ptr(fp, args.clone());
+    }
+}
+
+#[cfg(windows)]
+pub fn window_to_dynamic(window: &Window) -> Dynamic {
+    let mut map = Map::new();
+
+    map.insert("id".into(), window.id.into());
+    map.insert("title".into(), window.title.clone().into());
+    map.insert("process_name".into(), window.get_process_name().into());
+    map.insert("process_path".into(), window.get_process_path().into());
+
+    map.into()
+}
@@ -1,7 +1,15 @@
 use rhai::Engine;
+#[cfg(not(windows))]
+use rhai::{Map, RegisterFn};
 
+#[cfg(windows)]
 mod popup;
 
 pub fn init(engine: &mut Engine) {
+    #[cfg(windows)]
     popup::init(engine);
+
+    // popups can't be shown on other platforms, but a config that creates one is still valid
+    #[cfg(not(windows))]
+    engine.register_fn("popup_new", |_: Map| {});
 }
@@ -40,8 +40,9 @@ pub fn init(engine: &mut Engine) {
                                     }
                                     "cb" => {
                                         let fp = val.cast::<FnPtr>();
-                                        let idx = engine::add_callback(fp);
-                                        action.cb = Some(Arc::new(move || engine::call(idx)));
+                                        action.cb = Some(Arc::new(move || {
+                                            engine::call_fn_ptr(fp.clone(), Vec::new())
+                                        }));
                                     }
                                     _ => {}
                                 };
@@ -1,6 +1,11 @@
+mod diagnostics;
 pub mod engine;
 mod functions;
+pub mod hooks;
 mod lib;
 mod modules;
+mod overrides;
+mod resolver;
 mod syntax;
+pub mod timers;
 mod types;
@@ -1,13 +1,27 @@
-use rhai::module_resolvers::StaticModuleResolver;
+use rhai::{module_resolvers::StaticModuleResolver, EvalAltResult, Position};
 
 mod components;
+mod env;
+mod fs;
 mod http;
+mod json;
+mod process;
+mod state;
+
+fn runtime_error(message: impl ToString) -> Box<EvalAltResult> {
+    EvalAltResult::ErrorRuntime(message.to_string(), Position::none()).into()
+}
 
 pub fn new() -> StaticModuleResolver {
     let mut resolver = StaticModuleResolver::new();
 
     resolver.insert("nog/components", components::new());
+    resolver.insert("nog/env", env::new());
+    resolver.insert("nog/fs", fs::new());
     resolver.insert("nog/http", http::new());
+    resolver.insert("nog/json", json::new());
+    resolver.insert("nog/process", process::new());
+    resolver.insert("nog/state", state::new());
 
     resolver
 }
@@ -4,12 +4,31 @@ use crate::{
     config::rhai::engine::AST,
     config::rhai::engine::ENGINE,
     config::rhai::engine::SCOPE,
+    config::theme::Theme,
     display::Display,
+    CONFIG,
 };
 use log::error;
 use rhai::{Array, Dynamic, FnPtr, ImmutableString, Map, Module};
 use std::sync::Arc;
 
+/// A color is either a number or the name of a color of the active theme (e.g. "accent").
+/// Negative numbers and unknown names mean that the default color gets used.
+fn to_color(value: Option<&Dynamic>, theme: &Theme) -> Option<u32> {
+    let value = value?;
+
+    match value.type_name() {
+        "string" => theme
+            .get_slot(value.as_str().unwrap())
+            .map(|color| color as u32),
+        _ => value
+            .as_int()
+            .ok()
+            .filter(|color| *color >= 0)
+            .map(|color| color as u32),
+    }
+}
+
 fn create_component(name: ImmutableString, render_fn: FnPtr, options: Map) -> Component {
     let render_fn_name = render_fn.fn_name().to_string();
     let mut component = Component::new(
@@ -23,21 +42,19 @@ fn create_component(name: ImmutableString, render_fn: FnPtr, options: Map) -> Co
                 .map_err(|e| error!("{}", e.to_string()))
                 .unwrap_or_default();
 
+            let theme = CONFIG.lock().unwrap().get_theme();
+
             let return_value = result
                 .iter()
                 .map(|x| match x.type_name() {
                     "string" => Some(ComponentText::Basic(x.as_str().unwrap().to_string())),
                     "array" => {
                         let tuple = x.clone().cast::<Array>();
-                        let fg = tuple.get(0).unwrap().as_int().unwrap();
-                        let bg = tuple.get(1).unwrap().as_int().unwrap();
+                        let fg = to_color(tuple.get(0), &theme);
+                        let bg = to_color(tuple.get(1), &theme);
                         let text = tuple.get(2).unwrap().as_str().unwrap().to_string();
 
-                        Some(ComponentText::Colored(
-                            if fg < 0 { None } else { Some(fg as u32) },
-                            if bg < 0 { None } else { Some(bg as u32) },
-                            text,
-                        ))
+                        Some(ComponentText::Colored(fg, bg, text))
                     }
                     _ => None,
                 })
@@ -64,7 +81,7 @@ fn create_component(name: ImmutableString, render_fn: FnPtr, options: Map) -> Co
                             &mut *scope,
                             &*ast,
                             &fn_name,
-                            (component.clone(), *display, idx as i32),
+                            (component.clone(), display.clone(), idx as i32),
                         )
                         .map_err(|e| error!("{}", e.to_string()));
                 }));
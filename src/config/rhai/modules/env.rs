@@ -0,0 +1,35 @@
+use crate::util;
+use rhai::{Dynamic, EvalAltResult, ImmutableString, Map, Module};
+
+fn get(name: ImmutableString) -> Result<Dynamic, Box<EvalAltResult>> {
+    Ok(std::env::var(name.as_str())
+        .map(Dynamic::from)
+        .unwrap_or_else(|_| ().into()))
+}
+
+fn vars() -> Result<Map, Box<EvalAltResult>> {
+    Ok(std::env::vars()
+        .map(|(key, value)| (key.into(), value.into()))
+        .collect())
+}
+
+fn hostname() -> Result<String, Box<EvalAltResult>> {
+    Ok(util::get_hostname())
+}
+
+fn username() -> Result<String, Box<EvalAltResult>> {
+    Ok(std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_default())
+}
+
+pub fn new() -> Module {
+    let mut module = Module::new();
+
+    module.set_fn_1("get", get);
+    module.set_fn_0("vars", vars);
+    module.set_fn_0("hostname", hostname);
+    module.set_fn_0("username", username);
+
+    module
+}
@@ -0,0 +1,81 @@
+use super::runtime_error;
+use crate::config::location;
+use rhai::{Array, Dynamic, EvalAltResult, ImmutableString, Module};
+use std::path::{Path, PathBuf};
+
+/// Resolves the path relative to the config folder.
+///
+/// Scripts can only access files inside of the config folder, so the resolved path gets
+/// rejected if it points anywhere else (e.g. by using `..`).
+fn resolve(path: &str) -> Result<PathBuf, Box<EvalAltResult>> {
+    let root = location::get_config_dir()
+        .canonicalize()
+        .map_err(|e| runtime_error(format!("Failed to access the config folder: {}", e)))?;
+    let path = root.join(path);
+
+    // files that don't exist yet can't be canonicalized, so their parent gets used instead
+    let resolved = match (path.canonicalize(), path.parent(), path.file_name()) {
+        (Ok(resolved), _, _) => resolved,
+        (Err(_), Some(parent), Some(name)) => parent
+            .canonicalize()
+            .map(|parent| parent.join(name))
+            .map_err(|e| runtime_error(format!("{}: {}", path.display(), e)))?,
+        (Err(e), _, _) => return Err(runtime_error(format!("{}: {}", path.display(), e))),
+    };
+
+    if !resolved.starts_with(&root) {
+        return Err(runtime_error(format!(
+            "{} is outside of the config folder",
+            path.display()
+        )));
+    }
+
+    Ok(resolved)
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> Box<EvalAltResult> {
+    runtime_error(format!("{}: {}", path.display(), e))
+}
+
+fn read(path: ImmutableString) -> Result<String, Box<EvalAltResult>> {
+    let path = resolve(&path)?;
+
+    std::fs::read_to_string(&path).map_err(|e| io_error(&path, e))
+}
+
+fn write(path: ImmutableString, content: ImmutableString) -> Result<(), Box<EvalAltResult>> {
+    let path = resolve(&path)?;
+
+    std::fs::write(&path, content.as_str()).map_err(|e| io_error(&path, e))
+}
+
+fn exists(path: ImmutableString) -> Result<bool, Box<EvalAltResult>> {
+    Ok(resolve(&path)?.exists())
+}
+
+fn list(path: ImmutableString) -> Result<Array, Box<EvalAltResult>> {
+    let path = resolve(&path)?;
+    let mut names = Vec::new();
+
+    for entry in std::fs::read_dir(&path).map_err(|e| io_error(&path, e))? {
+        let entry = entry.map_err(|e| io_error(&path, e))?;
+
+        names.push(entry.file_name().to_string_lossy().to_string());
+    }
+
+    names.sort();
+
+    Ok(names.into_iter().map(Dynamic::from).collect())
+}
+
+pub fn new() -> Module {
+    let mut module = Module::new();
+
+    module.set_fn_1("read", read);
+    module.set_fn_2("write", write);
+    module.set_fn_1("exists", exists);
+    module.set_fn_0("list", || list("".into()));
+    module.set_fn_1("list", list);
+
+    module
+}
@@ -1,22 +1,147 @@
-use crate::config::rhai::types::http_response::HttpResponse;
+use super::runtime_error;
+use crate::{config::rhai::types::http_response::HttpResponse, event::Event, CHANNEL};
+use lazy_static::lazy_static;
+use log::error;
 use reqwest::{
     blocking::{Client, RequestBuilder},
     Method,
 };
 use rhai::{
-    de::from_dynamic, Array, Dynamic, EvalAltResult, ImmutableString, Map, Module, FLOAT, INT,
+    de::from_dynamic, Array, Dynamic, EvalAltResult, FnPtr, ImmutableString, Map, Module, FLOAT,
+    INT,
 };
+use std::time::Duration;
 
-fn load_request_body(rb: RequestBuilder, body: Dynamic) -> RequestBuilder {
-    match body.type_name() {
+/// How long a request waits for the response if no timeout is given
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+lazy_static! {
+    /// Shared by every request, so connections get reused
+    static ref CLIENT: Client = Client::builder()
+        .timeout(DEFAULT_TIMEOUT)
+        .build()
+        .expect("Failed to create http client");
+}
+
+const OPTION_KEYS: &[&str] = &[
+    "method", "url", "body", "headers", "query", "timeout", "auth",
+];
+
+fn load_request_body(
+    rb: RequestBuilder,
+    body: Dynamic,
+) -> Result<RequestBuilder, Box<EvalAltResult>> {
+    Ok(match body.type_name() {
         "string" => rb.body(body.as_str().unwrap().to_string()),
         "int" => rb.body(body.as_int().unwrap().to_string()),
         "float" => rb.body(body.as_float().unwrap().to_string()),
         "bool" => rb.body(body.as_bool().unwrap().to_string()),
         "char" => rb.body(body.as_char().unwrap().to_string()),
         "()" => rb,
-        _ => rb.json(&from_dynamic::<serde_json::Value>(&body).unwrap()),
+        _ => rb.json(&from_dynamic::<serde_json::Value>(&body)?),
+    })
+}
+
+fn to_pairs(name: &str, value: Dynamic) -> Result<Vec<(String, String)>, Box<EvalAltResult>> {
+    let map = value
+        .try_cast::<Map>()
+        .ok_or_else(|| runtime_error(format!("{} has to be a map", name)))?;
+
+    Ok(map
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect())
+}
+
+/// Applies the auth option, which is either a token or a map with a user and a password
+fn load_auth(rb: RequestBuilder, auth: Dynamic) -> Result<RequestBuilder, Box<EvalAltResult>> {
+    if auth.type_name() == "string" {
+        return Ok(rb.bearer_auth(auth.as_str().unwrap()));
+    }
+
+    let auth = auth
+        .try_cast::<Map>()
+        .ok_or_else(|| runtime_error("auth has to be a token or a map"))?;
+
+    let user = auth
+        .get("user")
+        .map(|user| user.to_string())
+        .ok_or_else(|| runtime_error("auth is missing the user"))?;
+    let password = auth.get("password").map(|password| password.to_string());
+
+    Ok(rb.basic_auth(user, password))
+}
+
+fn build_request(options: Map) -> Result<RequestBuilder, Box<EvalAltResult>> {
+    let method = match options.get("method") {
+        Some(method) => Method::from_bytes(method.to_string().to_uppercase().as_bytes())
+            .map_err(|_| runtime_error(format!("Unknown http method {}", method)))?,
+        None => Method::GET,
+    };
+    let url = options
+        .get("url")
+        .map(|url| url.to_string())
+        .ok_or_else(|| runtime_error("The request is missing the url"))?;
+
+    let mut rb = CLIENT.request(method, &url);
+
+    for (key, value) in options {
+        rb = match key.as_str() {
+            "body" => load_request_body(rb, value)?,
+            "headers" => to_pairs("headers", value)?
+                .into_iter()
+                .fold(rb, |rb, (name, value)| rb.header(name.as_str(), value)),
+            "query" => rb.query(&to_pairs("query", value)?),
+            "timeout" => {
+                let ms = value
+                    .as_int()
+                    .map_err(|_| runtime_error("timeout has to be a number"))?;
+                rb.timeout(Duration::from_millis(ms.max(0) as u64))
+            }
+            "auth" => load_auth(rb, value)?,
+            "method" | "url" => rb,
+            key => {
+                return Err(runtime_error(format!(
+                    "Unknown request option {} (expected one of {})",
+                    key,
+                    OPTION_KEYS.join(", ")
+                )))
+            }
+        };
     }
+
+    Ok(rb)
+}
+
+fn send(rb: RequestBuilder) -> Result<HttpResponse, Box<EvalAltResult>> {
+    rb.send()
+        .and_then(HttpResponse::from_res)
+        .map_err(|e| runtime_error(format!("Request failed: {}", e)))
+}
+
+fn request_with_options(options: Map) -> Result<HttpResponse, Box<EvalAltResult>> {
+    send(build_request(options)?)
+}
+
+/// Sends the request on another thread and calls the callback with the response and the
+/// error on the main loop. One of them is always `()`.
+fn request_async(options: Map, callback: FnPtr) -> Result<(), Box<EvalAltResult>> {
+    let rb = build_request(options)?;
+
+    std::thread::spawn(move || {
+        let args: Vec<Dynamic> = match send(rb) {
+            Ok(response) => vec![Dynamic::from(response), ().into()],
+            Err(e) => vec![().into(), e.to_string().into()],
+        };
+
+        let _ = CHANNEL
+            .sender
+            .clone()
+            .send(Event::Callback(callback, args))
+            .map_err(|e| error!("{:?}", e));
+    });
+
+    Ok(())
 }
 
 fn request<T>(
@@ -27,13 +152,9 @@ fn request<T>(
 where
     T: Into<Dynamic>,
 {
-    let body = body.into();
-    let url = url.to_string();
-    let client = Client::new();
-    let mut rb = client.request(method, &url);
-    rb = load_request_body(rb, body);
+    let rb = CLIENT.request(method, url.as_str());
 
-    Ok(HttpResponse::from_res(rb.send().unwrap()))
+    send(load_request_body(rb, body.into())?)
 }
 
 macro_rules! register_methods {
@@ -56,5 +177,172 @@ pub fn new() -> Module {
     register_methods!(module, "get", Method::GET);
     register_methods!(module, "post", Method::POST);
 
+    module.set_fn_1("request", request_with_options);
+    module.set_fn_2("request_async", request_async);
+
     module
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        io::{Read, Write},
+        net::{TcpListener, TcpStream},
+        sync::mpsc::{channel, Receiver},
+        time::Instant,
+    };
+
+    /// Reads the head and the body of a request
+    fn read_request(stream: &mut TcpStream) -> String {
+        let mut request = Vec::new();
+        let mut buffer = [0; 1024];
+
+        loop {
+            let n = stream.read(&mut buffer).unwrap();
+            request.extend_from_slice(&buffer[..n]);
+
+            let text = String::from_utf8_lossy(&request).to_string();
+
+            if n == 0 {
+                return text;
+            }
+
+            if let Some(end) = text.find("\r\n\r\n") {
+                let length = text
+                    .lines()
+                    .find(|l| l.to_lowercase().starts_with("content-length:"))
+                    .and_then(|l| l[15..].trim().parse::<usize>().ok())
+                    .unwrap_or(0);
+
+                if text.len() >= end + 4 + length {
+                    return text;
+                }
+            }
+        }
+    }
+
+    /// Answers a single request with the response and returns the url of the stub and the
+    /// request it received
+    fn stub(response: &'static str, delay: Duration) -> (String, Receiver<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = channel();
+
+        std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let _ = sender.send(read_request(&mut stream));
+
+            std::thread::sleep(delay);
+
+            let _ = stream.write_all(response.as_bytes());
+        });
+
+        (url, receiver)
+    }
+
+    fn options(entries: Vec<(&str, Dynamic)>) -> Map {
+        entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect()
+    }
+
+    const JSON_RESPONSE: &str = "HTTP/1.1 201 Created\r\ncontent-type: application/json\r\ncontent-length: 10\r\nconnection: close\r\n\r\n{\"id\": 42}";
+
+    #[test]
+    fn sends_the_headers_the_query_and_the_auth() {
+        let (url, request) = stub(JSON_RESPONSE, Duration::from_millis(0));
+        let headers = options(vec![("x-test", "yes".into())]);
+        let query = options(vec![("q", "a b".into())]);
+
+        let response = request_with_options(options(vec![
+            ("method", "post".into()),
+            ("url", format!("{}/path", url).into()),
+            ("body", "hello".into()),
+            ("headers", headers.into()),
+            ("query", query.into()),
+            ("auth", "token".into()),
+        ]))
+        .unwrap();
+
+        let request = request.recv().unwrap().to_lowercase();
+
+        assert!(request.starts_with("post /path?q=a+b http/1.1"));
+        assert!(request.contains("x-test: yes"));
+        assert!(request.contains("authorization: bearer token"));
+        assert!(request.ends_with("hello"));
+
+        assert_eq!(response.status_code, 201);
+        assert_eq!(
+            response.headers.get("content-type").unwrap().to_string(),
+            "application/json"
+        );
+
+        let body = response.body.cast::<Map>();
+        assert_eq!(body.get("id").unwrap().as_int().unwrap(), 42);
+    }
+
+    #[test]
+    fn sends_basic_auth() {
+        let (url, request) = stub(JSON_RESPONSE, Duration::from_millis(0));
+        let auth = options(vec![("user", "user".into()), ("password", "pw".into())]);
+
+        request_with_options(options(vec![("url", url.into()), ("auth", auth.into())])).unwrap();
+
+        // base64 of user:pw
+        assert!(request
+            .recv()
+            .unwrap()
+            .to_lowercase()
+            .contains("authorization: basic dxnlcjpwdw=="));
+    }
+
+    #[test]
+    fn surfaces_errors() {
+        let unknown = request_with_options(options(vec![
+            ("url", "http://127.0.0.1".into()),
+            ("retries", 3.into()),
+        ]));
+        assert!(unknown
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("Unknown request option retries"));
+
+        let missing_url = request_with_options(Map::new());
+        assert!(missing_url
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("The request is missing the url"));
+
+        // nothing listens on the port once the listener got dropped
+        let url = {
+            let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+            format!("http://{}", listener.local_addr().unwrap())
+        };
+        let refused = request_with_options(options(vec![("url", url.into())]));
+        assert!(refused
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("Request failed"));
+    }
+
+    #[test]
+    fn stops_waiting_after_the_timeout() {
+        let (url, _request) = stub(JSON_RESPONSE, Duration::from_secs(5));
+        let start = Instant::now();
+
+        let response =
+            request_with_options(options(vec![("url", url.into()), ("timeout", 200.into())]));
+
+        assert!(response
+            .err()
+            .unwrap()
+            .to_string()
+            .contains("Request failed"));
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,30 @@
+use super::runtime_error;
+use rhai::{de::from_dynamic, ser::to_dynamic, Dynamic, EvalAltResult, ImmutableString, Module};
+
+fn parse(text: ImmutableString) -> Result<Dynamic, Box<EvalAltResult>> {
+    let value = serde_json::from_str::<serde_json::Value>(&text)
+        .map_err(|e| runtime_error(format!("Invalid json: {}", e)))?;
+
+    to_dynamic(value)
+}
+
+fn stringify(value: Dynamic, pretty: bool) -> Result<String, Box<EvalAltResult>> {
+    let value = from_dynamic::<serde_json::Value>(&value)?;
+
+    if pretty {
+        serde_json::to_string_pretty(&value)
+    } else {
+        serde_json::to_string(&value)
+    }
+    .map_err(runtime_error)
+}
+
+pub fn new() -> Module {
+    let mut module = Module::new();
+
+    module.set_fn_1("parse", parse);
+    module.set_fn_1("stringify", |value| stringify(value, false));
+    module.set_fn_2("stringify", stringify);
+
+    module
+}
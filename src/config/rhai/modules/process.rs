@@ -0,0 +1,40 @@
+use super::runtime_error;
+use crate::config::rhai::types::process::{ProcessHandle, ProcessOutput};
+use rhai::{Array, EvalAltResult, ImmutableString, Module, INT};
+use std::time::Duration;
+
+/// How long `exec` waits for the process if no timeout is given
+const DEFAULT_TIMEOUT: INT = 10000;
+
+fn to_args(args: Array) -> Vec<String> {
+    args.into_iter().map(|arg| arg.to_string()).collect()
+}
+
+fn spawn(program: ImmutableString, args: Array) -> Result<ProcessHandle, Box<EvalAltResult>> {
+    ProcessHandle::spawn(&program, &to_args(args))
+        .map_err(|e| runtime_error(format!("Failed to start {}: {}", program, e)))
+}
+
+fn exec(
+    program: ImmutableString,
+    args: Array,
+    timeout: INT,
+) -> Result<ProcessOutput, Box<EvalAltResult>> {
+    spawn(program.clone(), args)?
+        .wait_timeout(Duration::from_millis(timeout.max(0) as u64))
+        .map_err(|e| runtime_error(format!("Failed to run {}: {}", program, e)))
+}
+
+pub fn new() -> Module {
+    let mut module = Module::new();
+
+    module.set_fn_1("exec", |program| {
+        exec(program, Array::new(), DEFAULT_TIMEOUT)
+    });
+    module.set_fn_2("exec", |program, args| exec(program, args, DEFAULT_TIMEOUT));
+    module.set_fn_3("exec", exec);
+    module.set_fn_1("spawn", |program| spawn(program, Array::new()));
+    module.set_fn_2("spawn", spawn);
+
+    module
+}
@@ -0,0 +1,188 @@
+use crate::{
+    display::Display, keybindings::MODE, DISPLAYS, VISIBLE_WORKSPACES, WORKSPACE_ID, WORK_MODE,
+};
+#[cfg(windows)]
+use crate::{tile::Tile, tile_grid::TileGrid, GRIDS};
+use lazy_static::lazy_static;
+use rhai::{Array, Dynamic, Map, Module};
+use std::{
+    collections::HashMap,
+    sync::{Mutex, TryLockError},
+};
+
+lazy_static! {
+    /// The last snapshot of each function, which gets returned while its global is locked
+    static ref SNAPSHOTS: Mutex<HashMap<&'static str, Dynamic>> = Mutex::new(HashMap::new());
+}
+
+/// Creates a snapshot of the value behind the mutex without blocking.
+///
+/// These functions can get called while the global is locked (e.g. a bar gets rendered while a
+/// workspace changes), so waiting for the lock could deadlock. In that case the last snapshot
+/// gets returned instead, or `()` if there is none yet. A poisoned mutex still holds the latest
+/// value, so it gets used anyway.
+fn snapshot<T>(name: &'static str, lock: &Mutex<T>, f: impl Fn(&T) -> Dynamic) -> Dynamic {
+    let value = match lock.try_lock() {
+        Ok(value) => f(&value),
+        Err(TryLockError::Poisoned(e)) => f(&e.into_inner()),
+        Err(TryLockError::WouldBlock) => {
+            return SNAPSHOTS
+                .lock()
+                .unwrap()
+                .get(name)
+                .cloned()
+                .unwrap_or_else(|| ().into())
+        }
+    };
+
+    SNAPSHOTS.lock().unwrap().insert(name, value.clone());
+
+    value
+}
+
+fn display_to_map(display: &Display) -> Map {
+    let mut map = Map::new();
+
+    map.insert("hmonitor".into(), display.hmonitor.into());
+    map.insert("name".into(), display.name.clone().into());
+    map.insert("dpi".into(), (display.dpi as i32).into());
+    map.insert("is_primary".into(), display.is_primary.into());
+    map.insert("work_mode".into(), display.work_mode.into());
+    map.insert("left".into(), display.left.into());
+    map.insert("right".into(), display.right.into());
+    map.insert("top".into(), display.top.into());
+    map.insert("bottom".into(), display.bottom.into());
+
+    map
+}
+
+#[cfg(windows)]
+fn tile_to_map(tile: &Tile) -> Map {
+    let mut map = Map::new();
+
+    map.insert("id".into(), tile.window.id.into());
+    map.insert("title".into(), tile.window.title.clone().into());
+    map.insert(
+        "column".into(),
+        tile.column.map(Dynamic::from).unwrap_or_else(|| ().into()),
+    );
+    map.insert(
+        "row".into(),
+        tile.row.map(Dynamic::from).unwrap_or_else(|| ().into()),
+    );
+    map.insert(
+        "split_direction".into(),
+        format!("{:?}", tile.split_direction).into(),
+    );
+
+    map
+}
+
+#[cfg(windows)]
+fn grid_to_map(grid: &TileGrid) -> Map {
+    let mut map = Map::new();
+
+    map.insert("id".into(), grid.id.into());
+    map.insert("monitor".into(), grid.display.hmonitor.into());
+    map.insert("fullscreen".into(), grid.fullscreen.into());
+    map.insert(
+        "focused_window_id".into(),
+        grid.focused_window_id
+            .map(Dynamic::from)
+            .unwrap_or_else(|| ().into()),
+    );
+    map.insert(
+        "tiles".into(),
+        grid.tiles
+            .iter()
+            .map(|t| tile_to_map(t).into())
+            .collect::<Array>()
+            .into(),
+    );
+
+    map
+}
+
+#[cfg(windows)]
+fn focused_window() -> Dynamic {
+    let id = snapshot("workspace_id", &WORKSPACE_ID, |id| (*id).into());
+
+    snapshot("focused_window", &GRIDS, |grids| {
+        grids
+            .iter()
+            .find(|g| Some(g.id) == id.as_int().ok())
+            .and_then(|g| g.get_focused_tile())
+            .map(|t| {
+                let mut map = tile_to_map(t);
+                map.insert("process_name".into(), t.window.get_process_name().into());
+                map.insert("process_path".into(), t.window.get_process_path().into());
+                map.into()
+            })
+            .unwrap_or_else(|| ().into())
+    })
+}
+
+pub fn new() -> Module {
+    let mut module = Module::new();
+
+    module.set_fn_0("workspace_id", || {
+        Ok(snapshot("workspace_id", &WORKSPACE_ID, |id| (*id).into()))
+    });
+    module.set_fn_0("work_mode", || {
+        Ok(snapshot("work_mode", &WORK_MODE, |work_mode| {
+            (*work_mode).into()
+        }))
+    });
+    module.set_fn_0("mode", || {
+        Ok(snapshot("mode", &MODE, |mode| {
+            mode.clone().map(Dynamic::from).unwrap_or_else(|| ().into())
+        }))
+    });
+    module.set_fn_0("displays", || {
+        Ok(snapshot("displays", &DISPLAYS, |displays| {
+            displays
+                .iter()
+                .map(|d| display_to_map(d).into())
+                .collect::<Array>()
+                .into()
+        }))
+    });
+    module.set_fn_0("visible_workspaces", || {
+        Ok(snapshot(
+            "visible_workspaces",
+            &VISIBLE_WORKSPACES,
+            |visible_workspaces| {
+                visible_workspaces
+                    .iter()
+                    .map(|(hmonitor, id)| {
+                        let mut map = Map::new();
+                        map.insert("monitor".into(), (*hmonitor).into());
+                        map.insert("workspace".into(), (*id).into());
+                        map.into()
+                    })
+                    .collect::<Array>()
+                    .into()
+            },
+        ))
+    });
+    #[cfg(windows)]
+    module.set_fn_0("workspaces", || {
+        Ok(snapshot("workspaces", &GRIDS, |grids| {
+            grids
+                .iter()
+                .map(|g| grid_to_map(g).into())
+                .collect::<Array>()
+                .into()
+        }))
+    });
+    #[cfg(windows)]
+    module.set_fn_0("focused_window", || Ok(focused_window()));
+
+    // there are no workspaces on other platforms, because the window manager doesn't run there
+    #[cfg(not(windows))]
+    module.set_fn_0("workspaces", || Ok(Dynamic::from(Array::new())));
+    #[cfg(not(windows))]
+    module.set_fn_0("focused_window", || Ok(Dynamic::from(())));
+
+    module
+}
@@ -0,0 +1,120 @@
+use crate::config::{
+    field::{Field, FieldValue, FIELDS},
+    Config,
+};
+use lazy_static::lazy_static;
+use std::{ops::Range, sync::Mutex};
+
+/// An override block (e.g. `when_host`) whose condition was true
+struct Override {
+    name: String,
+    /// The settings that got changed by the block
+    changes: Vec<(&'static Field, FieldValue)>,
+    rules: Range<usize>,
+    keybindings: Range<usize>,
+    workspace_settings: Range<usize>,
+}
+
+lazy_static! {
+    static ref APPLIED: Mutex<Vec<Override>> = Mutex::new(Vec::new());
+    /// The fields that got assigned by each override block that is currently being evaluated,
+    /// where the innermost block comes last
+    static ref ASSIGNED: Mutex<Vec<Vec<&'static Field>>> = Mutex::new(Vec::new());
+}
+
+pub fn clear() {
+    APPLIED.lock().unwrap().clear();
+    ASSIGNED.lock().unwrap().clear();
+}
+
+/// Starts tracking the fields that get assigned, until the block gets recorded
+pub fn begin() {
+    ASSIGNED.lock().unwrap().push(Vec::new());
+}
+
+/// Remembers that the field got assigned by the override blocks that are being evaluated. The
+/// value doesn't matter, so setting a field to the value it already has still overrides it.
+pub fn track(field: &'static Field) {
+    for assigned in ASSIGNED.lock().unwrap().iter_mut() {
+        if !assigned.iter().any(|f| std::ptr::eq(*f, field)) {
+            assigned.push(field);
+        }
+    }
+}
+
+/// Tracks every field whose name starts with the prefix (e.g. `bar.`)
+pub fn track_prefix(prefix: &str) {
+    for field in FIELDS.iter().filter(|f| f.name.starts_with(prefix)) {
+        track(field);
+    }
+}
+
+/// Remembers what the block of the override changed, given the config before and after the block
+/// got evaluated.
+pub fn record(name: String, before: &Config, after: &Config) {
+    let changes = ASSIGNED
+        .lock()
+        .unwrap()
+        .pop()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|field| (field, field.get(after)))
+        .collect();
+
+    APPLIED.lock().unwrap().push(Override {
+        name,
+        changes,
+        rules: before.rules.len()..after.rules.len(),
+        keybindings: before.keybindings.len()..after.keybindings.len(),
+        workspace_settings: before.workspace_settings.len()..after.workspace_settings.len(),
+    });
+}
+
+/// Moves the items that got added by an override in front of the other ones. The first matching
+/// item wins, so this is what makes an override take precedence over the base config.
+fn prioritize<T>(items: Vec<T>, ranges: Vec<&Range<usize>>) -> Vec<T> {
+    let (mut overridden, base): (Vec<_>, Vec<_>) = items
+        .into_iter()
+        .enumerate()
+        .partition(|(i, _)| ranges.iter().any(|range| range.contains(i)));
+
+    overridden.extend(base);
+    overridden.into_iter().map(|(_, item)| item).collect()
+}
+
+/// Merges the applied overrides into the config and returns their names.
+///
+/// The settings of an override get set again, so they replace the ones of the base config even if
+/// those come after the override in the file.
+pub fn apply(config: &mut Config) -> Vec<String> {
+    let applied = std::mem::replace(&mut *APPLIED.lock().unwrap(), Vec::new());
+
+    for (i, rule) in config.rules.iter_mut().enumerate() {
+        rule.is_override = applied.iter().any(|o| o.rules.contains(&i));
+    }
+
+    config.rules = prioritize(
+        std::mem::take(&mut config.rules),
+        applied.iter().map(|o| &o.rules).collect(),
+    );
+    config.keybindings = prioritize(
+        std::mem::take(&mut config.keybindings),
+        applied.iter().map(|o| &o.keybindings).collect(),
+    );
+    config.workspace_settings = prioritize(
+        std::mem::take(&mut config.workspace_settings),
+        applied.iter().map(|o| &o.workspace_settings).collect(),
+    );
+
+    applied
+        .into_iter()
+        .map(|o| {
+            for (field, value) in o.changes {
+                // the value was valid when the block set it
+                let _ = field.set(config, value);
+            }
+
+            o.name
+        })
+        .collect()
+}
@@ -0,0 +1,54 @@
+use super::diagnostics;
+use lazy_static::lazy_static;
+use rhai::{
+    module_resolvers::FileModuleResolver, Engine, EvalAltResult, Module, ModuleResolver, Position,
+};
+use std::{path::PathBuf, sync::Mutex};
+
+lazy_static! {
+    /// The files that got resolved since the last call of `take_resolved_files`
+    static ref RESOLVED_FILES: Mutex<Vec<PathBuf>> = Mutex::new(Vec::new());
+}
+
+pub fn take_resolved_files() -> Vec<PathBuf> {
+    std::mem::replace(&mut *RESOLVED_FILES.lock().unwrap(), Vec::new())
+}
+
+/// Resolves modules relative to the config folder and keeps track of which file is currently
+/// getting evaluated, so diagnostics point to the right file.
+pub struct FileTrackingResolver {
+    base_path: PathBuf,
+    inner: FileModuleResolver,
+}
+
+impl FileTrackingResolver {
+    pub fn new(base_path: PathBuf) -> Self {
+        Self {
+            inner: FileModuleResolver::new_with_path_and_extension(base_path.clone(), "nog"),
+            base_path,
+        }
+    }
+}
+
+impl ModuleResolver for FileTrackingResolver {
+    fn resolve(
+        &self,
+        engine: &Engine,
+        path: &str,
+        pos: Position,
+    ) -> Result<Module, Box<EvalAltResult>> {
+        let mut file_path = self.base_path.clone();
+
+        file_path.push(path);
+        file_path.set_extension("nog");
+
+        diagnostics::push_file(file_path.to_string_lossy().to_string());
+        RESOLVED_FILES.lock().unwrap().push(file_path);
+
+        let result = self.inner.resolve(engine, path, pos);
+
+        diagnostics::pop_file();
+
+        result
+    }
+}
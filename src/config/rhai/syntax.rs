@@ -1,62 +1,222 @@
-use super::engine::MODE;
+use super::{
+    diagnostics,
+    engine::{self, MODE},
+    overrides,
+};
 use crate::{
     bar::component::Component,
     config::{
-        bar_config::BarConfig, update_channel::UpdateChannel, Config, Rule, WorkspaceSetting,
+        bar_config::BarConfig,
+        field::{self, FieldValue},
+        rule::Matcher,
+        theme::{self, Theme},
+        update_channel::UpdateChannel,
+        workspace_setting::monitor_selector::MonitorSelector,
+        Config, MonitorSetting, Rule, WorkspaceSetting,
+    },
+    display,
+    keybindings::{
+        condition::ConditionalAction, keybinding::Keybinding, keybinding_type::KeybindingType,
     },
-    keybindings::{keybinding::Keybinding, keybinding_type::KeybindingType},
+    split_direction::SplitDirection,
+    util, CONFIG,
 };
-use log::error;
 use regex::Regex;
-use rhai::{Array, Dynamic, Engine, Map, ParseError};
+use rhai::{
+    Array, Dynamic, Engine, EvalAltResult, FnPtr, ImmutableString, Map, ParseError, Position,
+    RegisterResultFn,
+};
 use std::{
     str::FromStr,
     sync::{Arc, Mutex},
-    time::Duration,
 };
 
 #[macro_use]
 mod macros;
 
-fn set_config(config: &mut Config, key: String, value: Dynamic) {
-    set!(bool, config, use_border, key, value);
-    set!(i32, config, min_width, key, value);
-    set!(i32, config, min_height, key, value);
-    set!(bool, config, work_mode, key, value);
-    set!(bool, config, light_theme, key, value);
-    set!(bool, config, multi_monitor, key, value);
-    set!(bool, config, launch_on_startup, key, value);
-    set!(i32, config, outer_gap, key, value);
-    set!(i32, config, inner_gap, key, value);
-    set!(bool, config, remove_title_bar, key, value);
-    set!(bool, config, remove_task_bar, key, value);
-    set!(bool, config, display_app_bar, key, value);
-    if key == "update_interval" {
-        if value.type_name() != "i32" {
-            error!(
-                "{} has to be of type {} not {}",
-                "update_interval",
-                "i32",
-                value.type_name()
+const BAR_KEYS: &[&str] = &["color", "height", "font", "font_size", "components"];
+const BAR_SECTIONS: &[&str] = &["left", "center", "right"];
+const RULE_KEYS: &[&str] = &[
+    "manage",
+    "has_custom_titlebar",
+    "firefox",
+    "chromium",
+    "workspace_id",
+    "floating",
+    "size",
+    "position",
+    "monitor",
+    "follow",
+    "fullscreen",
+    "split_direction",
+    "remove_title_bar",
+    "on_manage",
+    "priority",
+];
+const MATCHER_KEYS: &[&str] = &["process", "path", "title", "class", "not", "any_of"];
+const UPDATE_CHANNEL_KEYS: &[&str] = &["branch", "repo", "version"];
+const WORKSPACE_KEYS: &[&str] = &["monitor", "text"];
+const MONITOR_KEYS: &[&str] = &["work_mode"];
+
+fn set_config(config: &mut Config, key: String, value: Dynamic, pos: Position) {
+    let names = field::top_level_names();
+
+    if !names.contains(&key.as_str()) {
+        diagnostics::unknown_key(&key, &names, pos);
+        return;
+    }
+
+    let field = field::find(&key).unwrap();
+
+    match FieldValue::from_dynamic(&value).filter(|v| v.get_type() == field.typ) {
+        Some(value) => match field.set(config, value) {
+            Ok(()) => overrides::track(field),
+            Err(e) => diagnostics::error(e, pos, None),
+        },
+        None => diagnostics::type_error(&key, &field.typ.to_string(), &value, pos),
+    }
+}
+
+fn runtime_error(message: String) -> Box<EvalAltResult> {
+    EvalAltResult::ErrorRuntime(message, Position::none()).into()
+}
+
+fn parse_keybinding(key: &str, pos: Position) -> Option<Keybinding> {
+    match Keybinding::from_str(key) {
+        Ok(kb) => Some(kb),
+        Err(e) => {
+            diagnostics::error(
+                format!("{} is not a valid key combination: {}", key, e),
+                pos,
+                Some("use a combination like \"Alt+Control+A\"".into()),
             );
-        } else {
-            config.update_interval = Duration::from_secs(value.clone().cast::<u64>() * 60);
+            None
         }
     }
-    if key == "default_update_channel" {
-        if value.type_name() != "string" {
-            error!(
-                "{} has to be of type {} not {}",
-                "default_update_channel",
-                "String",
-                value.type_name()
+}
+
+fn parse_pattern(pattern: &str, pos: Position) -> Option<Regex> {
+    match Regex::new(&format!("^{}$", pattern)) {
+        Ok(regex) => Some(regex),
+        Err(e) => {
+            diagnostics::error(
+                format!("{} is not a valid pattern: {}", pattern, e),
+                pos,
+                None,
             );
-        } else {
-            config.default_update_channel = Some(value.as_str().unwrap().to_string());
+            None
+        }
+    }
+}
+
+/// Accepts either a pattern, which matches the process name or the title, or a map of patterns
+/// that all have to match. Returns `None` if any part of the matcher is invalid or if it doesn't
+/// contain anything to match, because it would match every window.
+fn to_matcher(value: &Dynamic, pos: Position) -> Option<Matcher> {
+    match value.type_name() {
+        "string" => parse_pattern(value.as_str().unwrap(), pos).map(Matcher::from_pattern),
+        "map" => {
+            let settings = value.clone().cast::<Map>();
+            let mut matcher = Matcher::default();
+
+            diagnostics::check_keys(&settings, MATCHER_KEYS, pos);
+
+            for (key, value) in settings.iter() {
+                let key = key.as_str();
+
+                match key {
+                    "not" => matcher.not = Some(Box::new(to_matcher(value, pos)?)),
+                    "any_of" => match value.clone().try_cast::<Array>() {
+                        Some(matchers) => {
+                            matcher.any_of = matchers
+                                .iter()
+                                .map(|m| to_matcher(m, pos))
+                                .collect::<Option<Vec<Matcher>>>()?;
+                        }
+                        None => {
+                            diagnostics::type_error(key, "Array", value, pos);
+                            return None;
+                        }
+                    },
+                    "process" | "path" | "title" | "class" => {
+                        let pattern = match value.as_str() {
+                            Ok(pattern) => parse_pattern(pattern, pos)?,
+                            Err(_) => {
+                                diagnostics::type_error(key, "String", value, pos);
+                                return None;
+                            }
+                        };
+
+                        match key {
+                            "process" => matcher.process = Some(pattern),
+                            "path" => matcher.path = Some(pattern),
+                            "title" => matcher.title = Some(pattern),
+                            _ => matcher.class = Some(pattern),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+
+            if matcher.is_empty() {
+                diagnostics::error(
+                    "the matcher doesn't contain any pattern, so it would match every window"
+                        .into(),
+                    pos,
+                    Some(format!("use at least one of {}", MATCHER_KEYS.join(", "))),
+                );
+                return None;
+            }
+
+            Some(matcher)
+        }
+        _ => {
+            diagnostics::type_error("matcher", "String or Map", value, pos);
+            None
+        }
+    }
+}
+
+/// Converts an array of two numbers (e.g. `[800, 600]`) into a tuple
+fn to_pair(key: &str, value: &Dynamic, pos: Position) -> Option<(i32, i32)> {
+    let pair = value
+        .clone()
+        .try_cast::<Array>()
+        .filter(|array| array.len() == 2)
+        .and_then(|array| Some((array[0].as_int().ok()?, array[1].as_int().ok()?)));
+
+    if pair.is_none() {
+        diagnostics::type_error(key, "[i32, i32]", value, pos);
+    }
+
+    pair
+}
+
+fn to_monitor_selector(value: &Dynamic, pos: Position) -> Option<MonitorSelector> {
+    match value.type_name() {
+        "i32" => Some(value.as_int().unwrap().into()),
+        "string" => Some(value.as_str().unwrap().to_string().into()),
+        _ => {
+            diagnostics::type_error("monitor", "i32 or String", value, pos);
+            None
         }
     }
 }
 
+/// Accepts either a single monitor or an array of monitors, where each item is a fallback for the previous one.
+fn to_monitor_selectors(value: &Dynamic, pos: Position) -> Vec<MonitorSelector> {
+    if value.type_name() == "array" {
+        value
+            .clone()
+            .cast::<Array>()
+            .iter()
+            .filter_map(|v| to_monitor_selector(v, pos))
+            .collect()
+    } else {
+        to_monitor_selector(value, pos).into_iter().collect()
+    }
+}
+
 pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(), Box<ParseError>> {
     let cfg = config.clone();
     engine.register_custom_syntax(
@@ -64,10 +224,35 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
         0, // the number of new variables declared within this custom syntax
         move |engine, ctx, scope, inputs| {
             let key = get_string!(engine, ctx, scope, inputs, 0);
-            let binding = get_type!(engine, ctx, scope, inputs, 1, KeybindingType);
-            let mut kb = Keybinding::from_str(&key).unwrap();
+            let binding = get_dynamic!(engine, ctx, scope, inputs, 1);
+
+            // the action already reported why it got skipped
+            if binding.is::<()>() {
+                return Ok(().into());
+            }
 
-            kb.typ = binding;
+            let (typ, condition) = match binding.clone().try_cast::<ConditionalAction>() {
+                Some(action) => (action.typ, Some(action.condition)),
+                None => match binding.clone().try_cast::<KeybindingType>() {
+                    Some(typ) => (typ, None),
+                    None => {
+                        diagnostics::type_error(
+                            "argument 2",
+                            "KeybindingType",
+                            &binding,
+                            get_position!(inputs, 1),
+                        );
+                        return Ok(().into());
+                    }
+                },
+            };
+            let mut kb = match parse_keybinding(&key, get_position!(inputs, 0)) {
+                Some(kb) => kb,
+                None => return Ok(().into()),
+            };
+
+            kb.typ = typ;
+            kb.condition = condition;
             kb.mode = MODE.lock().unwrap().clone();
 
             cfg.lock().unwrap().keybindings.push(kb);
@@ -100,7 +285,10 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
                 let binding: KeybindingType =
                     engine.eval_expression(&format!("{}({})", binding_name, i))?;
 
-                let mut kb = Keybinding::from_str(&key).unwrap();
+                let mut kb = match parse_keybinding(&key, get_position!(inputs, 2)) {
+                    Some(kb) => kb,
+                    None => return Ok(().into()),
+                };
 
                 kb.typ = binding;
                 kb.mode = MODE.lock().unwrap().clone();
@@ -118,39 +306,109 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
         0,                  // the number of new variables declared within this custom syntax
         move |engine, ctx, scope, inputs| {
             let settings = get_map!(engine, ctx, scope, inputs, 0);
+            let pos = get_position!(inputs, 0);
             let mut bar_config: BarConfig = BarConfig::default();
 
+            diagnostics::check_keys(&settings, BAR_KEYS, pos);
+
             for (key, val) in settings {
                 if *key == "components" {
                     bar_config.components.empty();
-                    let map = val.cast::<Map>();
+
+                    let map = match val.clone().try_cast::<Map>() {
+                        Some(map) => map,
+                        None => {
+                            diagnostics::type_error("components", "Map", &val, pos);
+                            continue;
+                        }
+                    };
+
+                    diagnostics::check_keys(&map, BAR_SECTIONS, pos);
 
                     for (key, val) in map {
                         let key = key.to_string();
-                        let components = val.cast::<Array>();
+                        let list = match key.as_str() {
+                            "left" => &mut bar_config.components.left,
+                            "center" => &mut bar_config.components.center,
+                            "right" => &mut bar_config.components.right,
+                            _ => continue,
+                        };
+                        let components = match val.clone().try_cast::<Array>() {
+                            Some(components) => components,
+                            None => {
+                                diagnostics::type_error(&key, "Array", &val, pos);
+                                continue;
+                            }
+                        };
 
                         for v in components {
-                            let component = v.cast::<Component>();
-
-                            let list = match key.as_str() {
-                                "left" => &mut bar_config.components.left,
-                                "center" => &mut bar_config.components.center,
-                                "right" => &mut bar_config.components.right,
-                                _ => panic!(),
-                            };
-
-                            list.push(component);
+                            match v.clone().try_cast::<Component>() {
+                                Some(component) => list.push(component),
+                                None => diagnostics::type_error(&key, "Component", &v, pos),
+                            }
                         }
                     }
                 } else {
-                    set!(i32, bar_config, color, key, val);
-                    set!(i32, bar_config, height, key, val);
-                    set!(String, bar_config, font, key, val);
-                    set!(i32, bar_config, font_size, key, val);
+                    set!(i32, bar_config, color, key, val, pos);
+                    set!(i32, bar_config, height, key, val, pos);
+                    set!(String, bar_config, font, key, val, pos);
+                    set!(i32, bar_config, font_size, key, val, pos);
                 }
             }
 
             cfg.lock().unwrap().bar = bar_config;
+            // every setting of the bar gets replaced, not only the ones that are part of the map
+            overrides::track_prefix("bar.");
+
+            Ok(().into())
+        },
+    )?;
+
+    let cfg = config.clone();
+    engine.register_custom_syntax(
+        &["theme", "$expr$", "$expr$"], // the custom syntax
+        0, // the number of new variables declared within this custom syntax
+        move |engine, ctx, scope, inputs| {
+            let name = get_string!(engine, ctx, scope, inputs, 0);
+            let settings = get_map!(engine, ctx, scope, inputs, 1);
+            let pos = get_position!(inputs, 1);
+
+            let mut keys = theme::SLOTS.to_vec();
+            keys.push("light");
+
+            diagnostics::check_keys(&settings, &keys, pos);
+
+            let light = settings
+                .get("light")
+                .and_then(|light| light.as_bool().ok())
+                .unwrap_or(false);
+
+            // the colors that aren't set get derived from the background
+            let background = match settings.get("background") {
+                Some(background) => match background.as_int() {
+                    Ok(color) => field::swap_red_and_blue(color),
+                    Err(_) => {
+                        diagnostics::type_error("background", "i32", background, pos);
+                        return Ok(().into());
+                    }
+                },
+                None => cfg.lock().unwrap().bar.color,
+            };
+
+            let mut theme = Theme::derive(&name, background, light);
+
+            for (key, value) in settings.iter().filter(|(k, _)| k.as_str() != "light") {
+                match value.as_int() {
+                    Ok(color) => theme.set_slot(key, field::swap_red_and_blue(color)),
+                    Err(_) => diagnostics::type_error(key, "i32", value, pos),
+                }
+            }
+
+            let mut config = cfg.lock().unwrap();
+
+            // declaring a theme again replaces it, so imported themes can be customized
+            config.themes.retain(|t| t.name != name);
+            config.themes.push(theme);
 
             Ok(().into())
         },
@@ -165,7 +423,7 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
             let value = get_dynamic!(engine, ctx, scope, inputs, 1);
             let mut config = cfg.lock().unwrap();
 
-            set_config(&mut config, key, value);
+            set_config(&mut config, key, value, get_position!(inputs, 0));
 
             Ok(().into())
         },
@@ -179,7 +437,7 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
             let key = get_variable_name!(inputs, 0);
             let mut config = cfg.lock().unwrap();
 
-            set_config(&mut config, key, true.into());
+            set_config(&mut config, key, true.into(), get_position!(inputs, 0));
 
             Ok(().into())
         },
@@ -193,7 +451,7 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
             let key = get_variable_name!(inputs, 0);
             let mut config = cfg.lock().unwrap();
 
-            set_config(&mut config, key, false.into());
+            set_config(&mut config, key, false.into(), get_position!(inputs, 0));
 
             Ok(().into())
         },
@@ -204,19 +462,71 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
         &["rule", "$expr$", "$expr$"], // the custom syntax
         0, // the number of new variables declared within this custom syntax
         move |engine, ctx, scope, inputs| {
-            let pattern = get_string!(engine, ctx, scope, inputs, 0);
+            let matcher = get_dynamic!(engine, ctx, scope, inputs, 0);
             let settings = get_map!(engine, ctx, scope, inputs, 1);
+            let pos = get_position!(inputs, 1);
             let mut rule = Rule::default();
 
+            diagnostics::check_keys(&settings, RULE_KEYS, pos);
+
             for (key, value) in settings.iter().map(|(k, v)| (k.to_string(), v)) {
-                set!(bool, rule, manage, key, value);
-                set!(bool, rule, has_custom_titlebar, key, value);
-                set!(bool, rule, firefox, key, value);
-                set!(bool, rule, chromium, key, value);
-                set!(i32, rule, workspace_id, key, value);
+                set!(bool, rule, manage, key, value, pos);
+                set!(bool, rule, has_custom_titlebar, key, value, pos);
+                set!(bool, rule, firefox, key, value, pos);
+                set!(bool, rule, chromium, key, value, pos);
+                set!(i32, rule, workspace_id, key, value, pos);
+                set!(bool, rule, floating, key, value, pos);
+                set!(bool, rule, follow, key, value, pos);
+                set!(bool, rule, fullscreen, key, value, pos);
+                set!(bool, rule, remove_title_bar, key, value, pos);
+                set!(i32, rule, priority, key, value, pos);
+
+                match key.as_str() {
+                    "size" => rule.size = to_pair(&key, value, pos),
+                    "position" => rule.position = to_pair(&key, value, pos),
+                    "monitor" => rule.monitor = to_monitor_selector(value, pos),
+                    "split_direction" => {
+                        rule.split_direction = value
+                            .as_str()
+                            .ok()
+                            .and_then(|direction| SplitDirection::from_str(direction).ok());
+
+                        if rule.split_direction.is_none() {
+                            diagnostics::error(
+                                format!("{} is not a valid split direction", value),
+                                pos,
+                                Some("use either \"Horizontal\" or \"Vertical\"".into()),
+                            );
+                        }
+                    }
+                    "on_manage" => match value.clone().try_cast::<FnPtr>() {
+                        Some(fp) => rule.on_manage = Some(engine::add_callback(fp)),
+                        None => diagnostics::type_error(&key, "Function", value, pos),
+                    },
+                    _ => {}
+                }
             }
 
-            rule.pattern = Regex::new(&format!("^{}$", pattern)).map_err(|e| e.to_string())?;
+            if !rule.floating
+                && (settings.contains_key("size") || settings.contains_key("position"))
+            {
+                diagnostics::error(
+                    "size and position only apply to floating windows".into(),
+                    pos,
+                    Some("add floating: true to the rule".into()),
+                );
+                return Ok(().into());
+            }
+
+            rule.matcher = match to_matcher(&matcher, get_position!(inputs, 0)) {
+                Some(matcher) => matcher,
+                None => return Ok(().into()),
+            };
+            rule.keys = settings
+                .keys()
+                .map(|key| key.to_string())
+                .filter(|key| RULE_KEYS.contains(&key.as_str()) && key != "priority")
+                .collect();
 
             cfg.lock().unwrap().rules.push(rule);
 
@@ -231,14 +541,17 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
         move |engine, ctx, scope, inputs| {
             let name = get_string!(engine, ctx, scope, inputs, 0);
             let settings = get_map!(engine, ctx, scope, inputs, 1);
+            let pos = get_position!(inputs, 1);
             let mut update_channel = UpdateChannel::default();
 
             update_channel.name = name;
 
+            diagnostics::check_keys(&settings, UPDATE_CHANNEL_KEYS, pos);
+
             for (key, value) in settings.iter().map(|(k, v)| (k.to_string(), v)) {
-                set!(String, update_channel, branch, key, value);
-                set!(String, update_channel, repo, key, value);
-                set!(String, update_channel, version, key, value);
+                set!(String, update_channel, branch, key, value, pos);
+                set!(String, update_channel, repo, key, value, pos);
+                set!(String, update_channel, version, key, value, pos);
             }
 
             cfg.lock().unwrap().update_channels.push(update_channel);
@@ -252,11 +565,15 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
         &["ignore", "$expr$"], // the custom syntax
         0,                     // the number of new variables declared within this custom syntax
         move |engine, ctx, scope, inputs| {
-            let pattern = get_string!(engine, ctx, scope, inputs, 0);
+            let matcher = get_dynamic!(engine, ctx, scope, inputs, 0);
             let mut rule = Rule::default();
 
-            rule.pattern = Regex::new(&format!("^{}$", pattern)).map_err(|e| e.to_string())?;
+            rule.matcher = match to_matcher(&matcher, get_position!(inputs, 0)) {
+                Some(matcher) => matcher,
+                None => return Ok(().into()),
+            };
             rule.manage = false;
+            rule.keys = vec!["manage".into()];
 
             cfg.lock().unwrap().rules.push(rule);
 
@@ -271,13 +588,18 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
         move |engine, ctx, scope, inputs| {
             let id = get_int!(engine, ctx, scope, inputs, 0);
             let settings = get_map!(engine, ctx, scope, inputs, 1);
+            let pos = get_position!(inputs, 1);
             let mut workspace = WorkspaceSetting::default();
 
             workspace.id = id;
 
+            diagnostics::check_keys(&settings, WORKSPACE_KEYS, pos);
+
             for (key, value) in settings.iter().map(|(k, v)| (k.to_string(), v)) {
-                set!(i32, workspace, monitor, key, value);
-                set!(String, workspace, text, key, value);
+                if key == "monitor" {
+                    workspace.monitors = to_monitor_selectors(value, pos);
+                }
+                set!(String, workspace, text, key, value, pos);
             }
 
             cfg.lock().unwrap().workspace_settings.push(workspace);
@@ -286,6 +608,34 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
         },
     )?;
 
+    let cfg = config.clone();
+    engine.register_custom_syntax(
+        &["monitor", "$expr$", "$expr$"], // the custom syntax
+        0, // the number of new variables declared within this custom syntax
+        move |engine, ctx, scope, inputs| {
+            let monitor = get_dynamic!(engine, ctx, scope, inputs, 0);
+            let settings = get_map!(engine, ctx, scope, inputs, 1);
+            let pos = get_position!(inputs, 1);
+            let mut monitor_setting = MonitorSetting::default();
+
+            if let Some(selector) = to_monitor_selector(&monitor, get_position!(inputs, 0)) {
+                monitor_setting.monitor = selector;
+            } else {
+                return Ok(().into());
+            }
+
+            diagnostics::check_keys(&settings, MONITOR_KEYS, pos);
+
+            for (key, value) in settings.iter().map(|(k, v)| (k.to_string(), v)) {
+                set!(bool, monitor_setting, work_mode, key, value, pos);
+            }
+
+            cfg.lock().unwrap().monitor_settings.push(monitor_setting);
+
+            Ok(().into())
+        },
+    )?;
+
     let cfg = config.clone();
     engine.register_custom_syntax(
         &["mode", "$expr$", "$expr$", "$block$"], // the custom syntax
@@ -294,10 +644,11 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
             let name = get_string!(engine, ctx, scope, inputs, 0);
             let key = get_string!(engine, ctx, scope, inputs, 1);
 
-            let mut kb = Keybinding::from_str(&key).unwrap();
-            kb.typ = KeybindingType::ToggleMode(name.clone());
+            if let Some(mut kb) = parse_keybinding(&key, get_position!(inputs, 1)) {
+                kb.typ = KeybindingType::ToggleMode(name.clone());
 
-            cfg.lock().unwrap().keybindings.push(kb);
+                cfg.lock().unwrap().keybindings.push(kb);
+            }
 
             *MODE.lock().unwrap() = Some(name);
 
@@ -309,5 +660,194 @@ pub fn init(engine: &mut Engine, config: &mut Arc<Mutex<Config>>) -> Result<(),
         },
     )?;
 
+    let cfg = config.clone();
+    engine.register_custom_syntax(
+        &["when_host", "$expr$", "$block$"], // the custom syntax
+        0, // the number of new variables declared within this custom syntax
+        move |engine, ctx, scope, inputs| {
+            let host = get_string!(engine, ctx, scope, inputs, 0);
+
+            // hostnames on windows are case insensitive
+            if !host.eq_ignore_ascii_case(&util::get_hostname()) {
+                return Ok(().into());
+            }
+
+            let before = cfg.lock().unwrap().clone();
+
+            overrides::begin();
+            engine.eval_expression_tree(ctx, scope, inputs.get(1).unwrap())?;
+
+            let after = cfg.lock().unwrap();
+            overrides::record(format!("when_host({:?})", host), &before, &after);
+
+            Ok(().into())
+        },
+    )?;
+
+    let cfg = config.clone();
+    engine.register_custom_syntax(
+        &["when_displays", "$expr$", "$block$"], // the custom syntax
+        0, // the number of new variables declared within this custom syntax
+        move |engine, ctx, scope, inputs| {
+            let count = get_int!(engine, ctx, scope, inputs, 0);
+
+            if count != display::count() {
+                return Ok(().into());
+            }
+
+            let before = cfg.lock().unwrap().clone();
+
+            overrides::begin();
+            engine.eval_expression_tree(ctx, scope, inputs.get(1).unwrap())?;
+
+            let after = cfg.lock().unwrap();
+            overrides::record(format!("when_displays({})", count), &before, &after);
+
+            Ok(().into())
+        },
+    )?;
+
+    let cfg = config.clone();
+    engine.register_result_fn("get_config", move |name: ImmutableString| {
+        let field = field::find(&name).map_err(runtime_error)?;
+
+        // the active config can't be used while the config is evaluated, because it could be
+        // the one that is being initialized
+        let value = if engine::is_evaluating() {
+            field.get(&cfg.lock().unwrap())
+        } else {
+            field.get(&CONFIG.lock().unwrap())
+        };
+
+        Ok(value.into())
+    });
+
+    let cfg = config.clone();
+    engine.register_result_fn(
+        "set_config",
+        move |name: ImmutableString, value: Dynamic| {
+            let field = field::find(&name).map_err(runtime_error)?;
+            let value = FieldValue::from_dynamic(&value).ok_or_else(|| {
+                runtime_error(format!(
+                    "{} expects a value of type {}, found {}",
+                    name,
+                    field.typ,
+                    value.type_name()
+                ))
+            })?;
+
+            if engine::is_evaluating() {
+                field
+                    .set(&mut cfg.lock().unwrap(), value)
+                    .map_err(runtime_error)?;
+                overrides::track(field);
+            } else {
+                let field = field::find_runtime(&name).map_err(runtime_error)?;
+
+                field::set_at_runtime(field, value).map_err(runtime_error)?;
+            }
+
+            Ok(().into())
+        },
+    );
+
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::rule::WindowProperties;
+
+    fn string(value: &str) -> Dynamic {
+        Dynamic::from(value.to_string())
+    }
+
+    fn map(entries: Vec<(&str, Dynamic)>) -> Dynamic {
+        let map: Map = entries
+            .into_iter()
+            .map(|(key, value)| (key.into(), value))
+            .collect();
+
+        Dynamic::from(map)
+    }
+
+    fn window(process_name: &str, title: &str) -> WindowProperties {
+        WindowProperties {
+            process_name: process_name.into(),
+            process_path: format!("C:\\Program Files\\{}", process_name),
+            title: title.into(),
+            class_name: "Window".into(),
+        }
+    }
+
+    fn matcher(value: Dynamic) -> Matcher {
+        to_matcher(&value, Position::none()).expect("the matcher should be valid")
+    }
+
+    #[test]
+    fn shorthand_matches_process_or_title() {
+        let matcher = matcher(string("firefox.exe"));
+
+        assert!(matcher.matches(&window("firefox.exe", "Mozilla Firefox")));
+        assert!(matcher.matches(&window("explorer.exe", "firefox.exe")));
+        assert!(!matcher.matches(&window("explorer.exe", "Explorer")));
+    }
+
+    #[test]
+    fn shorthand_with_backslash_matches_path() {
+        let matcher = matcher(string(r".*\\Program Files\\firefox\.exe"));
+
+        assert!(matcher.matches(&window("firefox.exe", "Mozilla Firefox")));
+        assert!(!matcher.matches(&window("chrome.exe", "Mozilla Firefox")));
+    }
+
+    #[test]
+    fn map_requires_every_pattern() {
+        let matcher = matcher(map(vec![
+            ("process", string("firefox.exe")),
+            ("title", string(".*Private Browsing")),
+        ]));
+
+        assert!(matcher.matches(&window("firefox.exe", "Mozilla Firefox Private Browsing")));
+        assert!(!matcher.matches(&window("firefox.exe", "Mozilla Firefox")));
+        assert!(!matcher.matches(&window("chrome.exe", "Private Browsing")));
+    }
+
+    #[test]
+    fn not_inverts_the_matcher() {
+        let matcher = matcher(map(vec![
+            ("process", string("firefox.exe")),
+            ("not", map(vec![("title", string("Picture-in-Picture"))])),
+        ]));
+
+        assert!(matcher.matches(&window("firefox.exe", "Mozilla Firefox")));
+        assert!(!matcher.matches(&window("firefox.exe", "Picture-in-Picture")));
+    }
+
+    #[test]
+    fn any_of_requires_one_match() {
+        let matcher = matcher(map(vec![(
+            "any_of",
+            Dynamic::from(vec![string("firefox.exe"), string("chrome.exe")] as Array),
+        )]));
+
+        assert!(matcher.matches(&window("firefox.exe", "Mozilla Firefox")));
+        assert!(matcher.matches(&window("chrome.exe", "Google Chrome")));
+        assert!(!matcher.matches(&window("explorer.exe", "Explorer")));
+    }
+
+    #[test]
+    fn rejects_matchers_without_patterns() {
+        let empty = vec![
+            map(vec![]),
+            map(vec![("proces", string("firefox.exe"))]),
+            map(vec![("not", map(vec![]))]),
+            map(vec![("any_of", Dynamic::from(Array::new()))]),
+        ];
+
+        for value in empty {
+            assert!(to_matcher(&value, Position::none()).is_none());
+        }
+    }
+}
@@ -1,17 +1,42 @@
+/// Evaluates the input and returns early from the custom syntax (skipping the statement) when the value doesn't have the expected type.
+macro_rules! get_checked {
+    ($engine: ident, $ctx: ident, $scope: ident, $inputs: ident, $index: expr, $typ: ty, $name: expr) => {{
+        let expr = $inputs.get($index).unwrap();
+        let value = $engine.eval_expression_tree($ctx, $scope, expr)?;
+
+        match value.clone().try_cast::<$typ>() {
+            Some(v) => v,
+            None => {
+                crate::config::rhai::diagnostics::type_error(
+                    &format!("argument {}", $index + 1),
+                    $name,
+                    &value,
+                    expr.position(),
+                );
+                return Ok(().into());
+            }
+        }
+    }};
+}
+
 macro_rules! get_int {
     ($engine: ident, $ctx: ident, $scope: ident, $inputs: ident, $index: expr) => {
-        $engine
-            .eval_expression_tree($ctx, $scope, $inputs.get($index).unwrap())?
-            .as_int()?
+        get_checked!($engine, $ctx, $scope, $inputs, $index, i32, "i32")
     };
 }
 
 macro_rules! get_string {
     ($engine: ident, $ctx: ident, $scope: ident, $inputs: ident, $index: expr) => {
-        $engine
-            .eval_expression_tree($ctx, $scope, $inputs.get($index).unwrap())?
-            .as_str()?
-            .to_string()
+        get_checked!(
+            $engine,
+            $ctx,
+            $scope,
+            $inputs,
+            $index,
+            rhai::ImmutableString,
+            "String"
+        )
+        .to_string()
     };
 }
 
@@ -23,17 +48,13 @@ macro_rules! get_dynamic {
 
 macro_rules! get_map {
     ($engine: ident, $ctx: ident, $scope: ident, $inputs: ident, $index: expr) => {
-        $engine
-            .eval_expression_tree($ctx, $scope, $inputs.get($index).unwrap())?
-            .cast::<rhai::Map>();
+        get_checked!($engine, $ctx, $scope, $inputs, $index, rhai::Map, "Map")
     };
 }
 
-macro_rules! get_type {
-    ($engine: ident, $ctx: ident, $scope: ident, $inputs: ident, $index: expr, $type: ty) => {
-        $engine
-            .eval_expression_tree($ctx, $scope, $inputs.get($index).unwrap())?
-            .cast::<$type>();
+macro_rules! get_position {
+    ($inputs: ident, $index: expr) => {
+        $inputs.get($index).unwrap().position()
     };
 }
 
@@ -49,14 +70,14 @@ macro_rules! get_variable_name {
 }
 
 macro_rules! set {
-    ($typ: ty, $config: ident, $prop: ident, $key: ident, $val: ident) => {{
+    ($typ: ty, $config: ident, $prop: ident, $key: ident, $val: ident, $pos: expr) => {{
         if $key == stringify!($prop) {
             if $val.type_name().to_uppercase() != stringify!($typ).to_uppercase() {
-                error!(
-                    "{} has to be of type {} not {}",
-                    stringify!($key),
+                crate::config::rhai::diagnostics::type_error(
+                    stringify!($prop),
                     stringify!($typ),
-                    $val.type_name()
+                    &$val,
+                    $pos,
                 );
             } else {
                 $config.$prop = $val.clone().cast::<$typ>().into();
@@ -0,0 +1,153 @@
+use super::engine;
+use crate::{event::Event, CHANNEL};
+use lazy_static::lazy_static;
+use log::error;
+use rhai::{Engine, FnPtr, RegisterFn};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicI32, Ordering},
+        Condvar, Mutex, Once,
+    },
+    time::{Duration, Instant},
+};
+
+// timers only fire in the main loop of the window manager, which doesn't run on other platforms
+#[cfg_attr(not(windows), allow(dead_code))]
+struct Timer {
+    /// Stored directly instead of in CALLBACKS, so timers that get created at runtime don't
+    /// grow it
+    callback: FnPtr,
+    due: Instant,
+    interval: Option<Duration>,
+    /// Whether the timer already got sent to the main loop and waits for its callback to run
+    pending: bool,
+}
+
+lazy_static! {
+    static ref TIMERS: Mutex<HashMap<i32, Timer>> = Mutex::new(HashMap::new());
+    /// The timers of the config that is being evaluated
+    static ref PENDING_TIMERS: Mutex<HashMap<i32, Timer>> = Mutex::new(HashMap::new());
+    static ref WAKEUP: Condvar = Condvar::new();
+    /// Ids never get reused, so a timer event that is still queued after a reload gets ignored
+    static ref NEXT_ID: AtomicI32 = AtomicI32::new(1);
+}
+
+static START: Once = Once::new();
+
+pub fn init(engine: &mut Engine) {
+    PENDING_TIMERS.lock().unwrap().clear();
+
+    engine.register_fn("set_timeout", |fp: FnPtr, ms: i32| add(fp, ms, false));
+    engine.register_fn("set_interval", |fp: FnPtr, ms: i32| add(fp, ms, true));
+    engine.register_fn("clear_timer", |id: i32| {
+        TIMERS.lock().unwrap().remove(&id);
+        PENDING_TIMERS.lock().unwrap().remove(&id);
+    });
+}
+
+/// Replaces the timers with the ones of the config that just got evaluated
+pub fn commit() {
+    let pending = std::mem::take(&mut *PENDING_TIMERS.lock().unwrap());
+
+    *TIMERS.lock().unwrap() = pending;
+
+    WAKEUP.notify_one();
+}
+
+fn add(fp: FnPtr, ms: i32, repeat: bool) -> i32 {
+    START.call_once(|| {
+        std::thread::spawn(run);
+    });
+
+    let id = NEXT_ID.fetch_add(1, Ordering::SeqCst);
+    let duration = Duration::from_millis(ms.max(1) as u64);
+
+    let timers = if engine::is_evaluating() {
+        &*PENDING_TIMERS
+    } else {
+        &*TIMERS
+    };
+
+    timers.lock().unwrap().insert(
+        id,
+        Timer {
+            callback: fp,
+            due: Instant::now() + duration,
+            interval: if repeat { Some(duration) } else { None },
+            pending: false,
+        },
+    );
+
+    WAKEUP.notify_one();
+
+    id
+}
+
+/// Sends every timer that is due to the main loop and sleeps until the next one is due.
+fn run() {
+    let mut timers = TIMERS.lock().unwrap();
+
+    loop {
+        let now = Instant::now();
+
+        for (id, timer) in timers.iter_mut() {
+            if !timer.pending && timer.due <= now {
+                timer.pending = true;
+
+                let _ = CHANNEL
+                    .sender
+                    .clone()
+                    .send(Event::Timer(*id))
+                    .map_err(|e| error!("{:?}", e));
+            }
+        }
+
+        let next_due = timers
+            .values()
+            .filter(|timer| !timer.pending)
+            .map(|timer| timer.due)
+            .min();
+
+        timers = match next_due {
+            Some(due) => {
+                WAKEUP
+                    .wait_timeout(timers, due.saturating_duration_since(Instant::now()))
+                    .unwrap()
+                    .0
+            }
+            None => WAKEUP.wait(timers).unwrap(),
+        };
+    }
+}
+
+/// Calls the callback of the timer. Intervals get scheduled again, timeouts get removed.
+#[cfg(windows)]
+pub fn fire(id: i32) {
+    let callback = {
+        let mut timers = TIMERS.lock().unwrap();
+
+        let timer = match timers.get_mut(&id) {
+            Some(timer) => timer,
+            None => return,
+        };
+        let callback = timer.callback.clone();
+
+        match timer.interval {
+            Some(interval) => {
+                timer.pending = false;
+                timer.due = Instant::now() + interval;
+            }
+            None => {
+                timers.remove(&id);
+            }
+        }
+
+        callback
+    };
+
+    WAKEUP.notify_one();
+
+    // the lock is released at this point, so the callback can create or clear timers
+    engine::call_fn_ptr(callback, Vec::new());
+}
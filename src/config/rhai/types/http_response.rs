@@ -1,4 +1,4 @@
-use rhai::{ser::to_dynamic, Dynamic};
+use rhai::{ser::to_dynamic, Dynamic, Map};
 
 #[derive(Copy, Clone)]
 pub enum ContentType {
@@ -17,30 +17,47 @@ pub struct HttpResponse {
     pub body: Dynamic,
     pub status_code: i32,
     pub content_type: ContentType,
+    pub headers: Map,
 }
 
 impl HttpResponse {
-    pub fn from_res(res: reqwest::blocking::Response) -> Self {
+    pub fn from_res(res: reqwest::blocking::Response) -> Result<Self, reqwest::Error> {
         let mut this = Self::default();
 
         this.status_code = res.status().as_u16() as i32;
 
-        let content_type = res
-            .headers()
+        for (name, value) in res.headers() {
+            if let Ok(value) = value.to_str() {
+                this.headers
+                    .insert(name.as_str().into(), value.to_string().into());
+            }
+        }
+
+        let content_type = this
+            .headers
             .get("content-type")
-            .map(|h| h.to_str().unwrap().to_string())
+            .map(|h| h.to_string())
             .unwrap_or_default();
 
-        let body = res.text().unwrap();
-        if content_type.contains("application/json") {
-            let json = serde_json::from_str::<serde_json::Value>(&body).unwrap();
-            let map = to_dynamic(json).unwrap();
-            this.body = map;
-            this.content_type = ContentType::Json;
+        let body = res.text()?;
+
+        // a body that claims to be json but isn't valid json is kept as text
+        let json = if content_type.contains("application/json") {
+            serde_json::from_str::<serde_json::Value>(&body)
+                .ok()
+                .and_then(|json| to_dynamic(json).ok())
         } else {
-            this.body = body.into();
+            None
         };
 
-        this
+        match json {
+            Some(json) => {
+                this.body = json;
+                this.content_type = ContentType::Json;
+            }
+            None => this.body = body.into(),
+        }
+
+        Ok(this)
     }
 }
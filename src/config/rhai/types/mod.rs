@@ -1,12 +1,42 @@
 use http_response::HttpResponse;
-use rhai::Engine;
+use process::{ProcessHandle, ProcessOutput};
+use rhai::{Dynamic, Engine, EvalAltResult, Position, RegisterResultFn};
 
 pub mod http_response;
+pub mod process;
+
+fn to_runtime_error(e: std::io::Error) -> Box<EvalAltResult> {
+    EvalAltResult::ErrorRuntime(e.to_string(), Position::none()).into()
+}
 
 pub fn init(engine: &mut Engine) {
     engine
         .register_type::<HttpResponse>()
         .register_get("body", |x: &mut HttpResponse| x.body.clone())
         .register_get("content_type", |x: &mut HttpResponse| x.content_type)
-        .register_get("status_code", |x: &mut HttpResponse| x.status_code);
+        .register_get("status_code", |x: &mut HttpResponse| x.status_code)
+        .register_get("headers", |x: &mut HttpResponse| x.headers.clone());
+
+    engine
+        .register_type::<ProcessOutput>()
+        .register_get("stdout", |x: &mut ProcessOutput| x.stdout.clone())
+        .register_get("stderr", |x: &mut ProcessOutput| x.stderr.clone())
+        .register_get("exit_code", |x: &mut ProcessOutput| x.exit_code)
+        .register_get("timed_out", |x: &mut ProcessOutput| x.timed_out);
+
+    engine
+        .register_type::<ProcessHandle>()
+        .register_get("pid", |x: &mut ProcessHandle| x.id());
+
+    engine.register_result_fn("poll", |x: &mut ProcessHandle| {
+        x.poll()
+            .map(|output| output.map(Dynamic::from).unwrap_or_else(|| ().into()))
+            .map_err(to_runtime_error)
+    });
+    engine.register_result_fn("is_running", |x: &mut ProcessHandle| {
+        x.is_running().map(Dynamic::from).map_err(to_runtime_error)
+    });
+    engine.register_result_fn("kill", |x: &mut ProcessHandle| {
+        x.kill().map(|_| ().into()).map_err(to_runtime_error)
+    });
 }
@@ -0,0 +1,240 @@
+use std::{
+    io::Read,
+    process::{Child, Command, Stdio},
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+/// How long `poll` waits for the pipes to be drained after the process exited. A process that
+/// inherited the pipes (e.g. a program that was started in the background) can keep them open
+/// after the process itself exited, so the readers can't be waited for indefinitely.
+const DRAIN_TIMEOUT: Duration = Duration::from_millis(100);
+
+#[derive(Default, Clone)]
+pub struct ProcessOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub exit_code: i32,
+    pub timed_out: bool,
+}
+
+/// A process that was started by a script. The output gets collected in the background, so
+/// the process never blocks on a full pipe.
+#[derive(Clone)]
+pub struct ProcessHandle {
+    child: Arc<Mutex<Child>>,
+    stdout: Arc<Mutex<Vec<u8>>>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+    readers: Arc<Mutex<Readers>>,
+}
+
+/// The threads that collect the output
+struct Readers {
+    /// Receives a message whenever a reader stops
+    finished: Receiver<()>,
+    running: usize,
+}
+
+fn collect(
+    mut source: impl Read + Send + 'static,
+    target: Arc<Mutex<Vec<u8>>>,
+    finished: Sender<()>,
+) {
+    std::thread::spawn(move || {
+        let mut buffer = [0; 1024];
+
+        while let Ok(n) = source.read(&mut buffer) {
+            if n == 0 {
+                break;
+            }
+
+            target.lock().unwrap().extend_from_slice(&buffer[..n]);
+        }
+
+        let _ = finished.send(());
+    });
+}
+
+impl ProcessHandle {
+    pub fn spawn(program: &str, args: &[String]) -> std::io::Result<Self> {
+        let mut command = Command::new(program);
+
+        command
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+
+        #[cfg(windows)]
+        {
+            use std::os::windows::process::CommandExt;
+            // CREATE_NO_WINDOW, otherwise every console program opens a new console
+            command.creation_flags(0x0800_0000);
+        }
+
+        let mut child = command.spawn()?;
+
+        let stdout = Arc::new(Mutex::new(Vec::new()));
+        let stderr = Arc::new(Mutex::new(Vec::new()));
+        let (sender, finished) = channel();
+        let mut running = 0;
+
+        if let Some(source) = child.stdout.take() {
+            collect(source, stdout.clone(), sender.clone());
+            running += 1;
+        }
+
+        if let Some(source) = child.stderr.take() {
+            collect(source, stderr.clone(), sender);
+            running += 1;
+        }
+
+        Ok(Self {
+            child: Arc::new(Mutex::new(child)),
+            stdout,
+            stderr,
+            readers: Arc::new(Mutex::new(Readers { finished, running })),
+        })
+    }
+
+    pub fn id(&self) -> i32 {
+        self.child.lock().unwrap().id() as i32
+    }
+
+    /// Returns the output of the process if it exited
+    pub fn poll(&self) -> std::io::Result<Option<ProcessOutput>> {
+        let status = match self.child.lock().unwrap().try_wait()? {
+            Some(status) => status,
+            None => return Ok(None),
+        };
+
+        // the process exited, so the readers usually stop as soon as the pipes are drained
+        let deadline = Instant::now() + DRAIN_TIMEOUT;
+        let mut readers = self.readers.lock().unwrap();
+
+        while readers.running > 0 {
+            match readers
+                .finished
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(()) => readers.running -= 1,
+                Err(_) => {
+                    // the remaining readers get detached, so later polls don't wait again
+                    readers.running = 0;
+                }
+            }
+        }
+
+        drop(readers);
+
+        Ok(Some(ProcessOutput {
+            stdout: String::from_utf8_lossy(&self.stdout.lock().unwrap()).to_string(),
+            stderr: String::from_utf8_lossy(&self.stderr.lock().unwrap()).to_string(),
+            exit_code: status.code().unwrap_or(-1),
+            timed_out: false,
+        }))
+    }
+
+    pub fn is_running(&self) -> std::io::Result<bool> {
+        Ok(self.child.lock().unwrap().try_wait()?.is_none())
+    }
+
+    pub fn kill(&self) -> std::io::Result<()> {
+        let mut child = self.child.lock().unwrap();
+
+        if child.try_wait()?.is_none() {
+            child.kill()?;
+            child.wait()?;
+        }
+
+        Ok(())
+    }
+
+    /// Waits until the process exits. The process gets killed if it takes longer than the timeout.
+    pub fn wait_timeout(&self, timeout: Duration) -> std::io::Result<ProcessOutput> {
+        let start = Instant::now();
+
+        loop {
+            if let Some(output) = self.poll()? {
+                return Ok(output);
+            }
+
+            if start.elapsed() >= timeout {
+                self.kill()?;
+
+                let mut output = self.poll()?.unwrap_or_default();
+                output.timed_out = true;
+
+                return Ok(output);
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
+
+#[cfg(all(test, not(windows)))]
+mod tests {
+    use super::*;
+
+    fn sh(script: &str) -> ProcessHandle {
+        ProcessHandle::spawn("sh", &["-c".to_string(), script.to_string()])
+            .expect("Failed to start sh")
+    }
+
+    #[test]
+    fn collects_the_output_and_the_exit_code() {
+        let output = sh("echo out; echo err >&2; exit 3")
+            .wait_timeout(Duration::from_secs(5))
+            .unwrap();
+
+        assert_eq!(output.stdout, "out\n");
+        assert_eq!(output.stderr, "err\n");
+        assert_eq!(output.exit_code, 3);
+        assert!(!output.timed_out);
+    }
+
+    #[test]
+    fn kills_the_process_after_the_timeout() {
+        let process = sh("echo started; sleep 5");
+        let start = Instant::now();
+
+        let output = process.wait_timeout(Duration::from_millis(200)).unwrap();
+
+        assert!(output.timed_out);
+        assert_eq!(output.exit_code, -1);
+        assert!(start.elapsed() < Duration::from_secs(2));
+        assert!(!process.is_running().unwrap());
+    }
+
+    #[test]
+    fn polls_and_kills_a_spawned_process() {
+        let process = sh("sleep 5");
+
+        assert!(process.id() > 0);
+        assert!(process.poll().unwrap().is_none());
+        assert!(process.is_running().unwrap());
+
+        process.kill().unwrap();
+
+        assert!(!process.is_running().unwrap());
+        assert_eq!(process.poll().unwrap().unwrap().exit_code, -1);
+    }
+
+    #[test]
+    fn background_processes_dont_block_poll() {
+        // the background process inherits stdout and keeps it open after sh exited
+        let start = Instant::now();
+
+        let output = sh("sleep 5 & echo done")
+            .wait_timeout(Duration::from_secs(3))
+            .unwrap();
+
+        assert_eq!(output.stdout, "done\n");
+        assert!(!output.timed_out);
+        assert!(start.elapsed() < Duration::from_secs(2));
+    }
+}
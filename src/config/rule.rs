@@ -1,24 +1,318 @@
+use super::workspace_setting::monitor_selector::MonitorSelector;
+use crate::{
+    config::rhai::engine::CALLBACKS, keybindings::keybinding_type::serialize_callback,
+    split_direction::SplitDirection,
+};
+#[cfg(windows)]
+use crate::{util, window::Window};
 use regex::Regex;
+use serde::{Serialize, Serializer};
+use std::fmt;
+#[cfg(windows)]
+use winapi::shared::windef::HWND;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Rule {
-    pub pattern: Regex,
+    pub matcher: Matcher,
     pub has_custom_titlebar: bool,
     pub manage: bool,
     pub chromium: bool,
     pub firefox: bool,
     pub workspace_id: i32,
+    /// Whether the window floats instead of getting managed
+    pub floating: bool,
+    /// The size of a floating window in logical units
+    pub size: Option<(i32, i32)>,
+    /// The position of a floating window relative to its display in logical units
+    pub position: Option<(i32, i32)>,
+    /// The monitor whose visible workspace the window gets moved to
+    pub monitor: Option<MonitorSelector>,
+    /// Whether the workspace the window gets moved to becomes the active one
+    pub follow: bool,
+    pub fullscreen: bool,
+    /// The direction in which the window gets inserted next to the focused tile
+    pub split_direction: Option<SplitDirection>,
+    /// Overrides the remove_title_bar setting for the window
+    pub remove_title_bar: Option<bool>,
+    /// The callback that gets called after the window got managed
+    #[serde(serialize_with = "serialize_on_manage")]
+    pub on_manage: Option<usize>,
+    /// Rules with a higher priority take precedence over the other ones
+    pub priority: i32,
+    /// Whether the rule got added by an override (e.g. `when_host`), which makes it take
+    /// precedence over the rules of the base config with the same priority
+    pub is_override: bool,
+    /// The keys of the settings that got set in the config, which are the ones that get merged
+    /// into other rules
+    pub keys: Vec<String>,
+}
+
+fn serialize_on_manage<S: Serializer>(
+    idx: &Option<usize>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match idx {
+        Some(idx) => serialize_callback(idx, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Serializes the regex by its pattern
+fn serialize_regex<S: Serializer>(regex: &Option<Regex>, serializer: S) -> Result<S::Ok, S::Error> {
+    match regex {
+        Some(regex) => serializer.serialize_str(regex.as_str()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// The properties of a window that a rule can match
+#[cfg(any(windows, test))]
+pub struct WindowProperties {
+    pub process_name: String,
+    pub process_path: String,
+    pub title: String,
+    pub class_name: String,
+}
+
+#[cfg(windows)]
+impl WindowProperties {
+    pub fn new(window: &Window) -> Self {
+        let process_path = window.get_process_path();
+
+        Self {
+            process_name: process_path.split('\\').last().unwrap_or_default().into(),
+            process_path,
+            title: window.title.clone(),
+            class_name: util::get_class_name_of_window(window.id as HWND).unwrap_or_default(),
+        }
+    }
+}
+
+/// Decides which windows a rule applies to. Every part that is set has to match.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Matcher {
+    /// The pattern of the `rule "<pattern>"` shorthand, which matches either the process name (or
+    /// the path if it contains a backslash) or the title
+    #[serde(serialize_with = "serialize_regex")]
+    pub pattern: Option<Regex>,
+    #[serde(serialize_with = "serialize_regex")]
+    pub process: Option<Regex>,
+    #[serde(serialize_with = "serialize_regex")]
+    pub path: Option<Regex>,
+    #[serde(serialize_with = "serialize_regex")]
+    pub title: Option<Regex>,
+    #[serde(serialize_with = "serialize_regex")]
+    pub class: Option<Regex>,
+    /// Has to NOT match the window
+    pub not: Option<Box<Matcher>>,
+    /// At least one of them has to match the window, if there are any
+    pub any_of: Vec<Matcher>,
+}
+
+impl Matcher {
+    pub fn from_pattern(pattern: Regex) -> Self {
+        Self {
+            pattern: Some(pattern),
+            ..Self::default()
+        }
+    }
+
+    /// Returns true if the matcher doesn't contain anything to match, in which case it would match
+    /// every window
+    pub fn is_empty(&self) -> bool {
+        self.patterns().iter().all(|(_, regex)| regex.is_none())
+            && self.not.is_none()
+            && self.any_of.is_empty()
+    }
+
+    fn patterns(&self) -> [(&'static str, &Option<Regex>); 5] {
+        [
+            ("pattern", &self.pattern),
+            ("process", &self.process),
+            ("path", &self.path),
+            ("title", &self.title),
+            ("class", &self.class),
+        ]
+    }
+
+    #[cfg(any(windows, test))]
+    pub fn matches(&self, window: &WindowProperties) -> bool {
+        let is_match = |regex: &Option<Regex>, value: &str| {
+            regex.as_ref().map(|r| r.is_match(value)).unwrap_or(true)
+        };
+
+        let matches_pattern = match &self.pattern {
+            Some(pattern) => {
+                // checks for path
+                let process = if pattern.as_str().contains('\\') {
+                    &window.process_path
+                } else {
+                    &window.process_name
+                };
+
+                pattern.is_match(process) || pattern.is_match(&window.title)
+            }
+            None => true,
+        };
+
+        matches_pattern
+            && is_match(&self.process, &window.process_name)
+            && is_match(&self.path, &window.process_path)
+            && is_match(&self.title, &window.title)
+            && is_match(&self.class, &window.class_name)
+            && self
+                .not
+                .as_ref()
+                .map(|m| !m.matches(window))
+                .unwrap_or(true)
+            && (self.any_of.is_empty() || self.any_of.iter().any(|m| m.matches(window)))
+    }
+}
+
+impl PartialEq for Matcher {
+    fn eq(&self, other: &Self) -> bool {
+        let as_str = |regex: &Option<Regex>| regex.as_ref().map(|r| r.as_str().to_string());
+
+        self.patterns()
+            .iter()
+            .zip(other.patterns().iter())
+            .all(|((_, a), (_, b))| as_str(a) == as_str(b))
+            && self.not == other.not
+            && self.any_of == other.any_of
+    }
+}
+
+impl fmt::Display for Matcher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = self
+            .patterns()
+            .iter()
+            .filter_map(|(name, regex)| regex.as_ref().map(|r| format!("{}: {}", name, r)))
+            .collect::<Vec<String>>();
+
+        if let Some(not) = &self.not {
+            parts.push(format!("not: ({})", not));
+        }
+
+        if !self.any_of.is_empty() {
+            let any_of = self
+                .any_of
+                .iter()
+                .map(|m| format!("({})", m))
+                .collect::<Vec<String>>();
+
+            parts.push(format!("any_of: [{}]", any_of.join(", ")));
+        }
+
+        f.write_str(&parts.join(", "))
+    }
+}
+
+impl PartialEq for Rule {
+    fn eq(&self, other: &Self) -> bool {
+        self.matcher == other.matcher
+            && self.has_custom_titlebar == other.has_custom_titlebar
+            && self.manage == other.manage
+            && self.chromium == other.chromium
+            && self.firefox == other.firefox
+            && self.workspace_id == other.workspace_id
+            && self.floating == other.floating
+            && self.size == other.size
+            && self.position == other.position
+            && self.monitor == other.monitor
+            && self.follow == other.follow
+            && self.fullscreen == other.fullscreen
+            && self.split_direction == other.split_direction
+            && self.remove_title_bar == other.remove_title_bar
+            && self.on_manage == other.on_manage
+            && self.priority == other.priority
+            && self.is_override == other.is_override
+            && self.keys == other.keys
+    }
 }
 
 impl Default for Rule {
     fn default() -> Self {
         Self {
-            pattern: Regex::new("").unwrap(),
+            matcher: Matcher::default(),
             has_custom_titlebar: false,
             manage: true,
             chromium: false,
             firefox: false,
             workspace_id: -1,
+            floating: false,
+            size: None,
+            position: None,
+            monitor: None,
+            follow: true,
+            fullscreen: false,
+            split_direction: None,
+            remove_title_bar: None,
+            on_manage: None,
+            priority: 0,
+            is_override: false,
+            keys: Vec::new(),
+        }
+    }
+}
+
+impl Rule {
+    /// Rules with a greater precedence win over the other ones, no matter whether the first
+    /// matching rule gets used or the matching rules get merged
+    pub fn precedence(&self) -> (i32, bool) {
+        (self.priority, self.is_override)
+    }
+
+    /// Overrides the settings of this rule with the ones that got set in the other rule
+    #[cfg(windows)]
+    pub fn merge(&mut self, other: &Rule) {
+        for key in &other.keys {
+            match key.as_str() {
+                "has_custom_titlebar" => self.has_custom_titlebar = other.has_custom_titlebar,
+                "manage" => self.manage = other.manage,
+                "chromium" => self.chromium = other.chromium,
+                "firefox" => self.firefox = other.firefox,
+                "workspace_id" => self.workspace_id = other.workspace_id,
+                "floating" => self.floating = other.floating,
+                "size" => self.size = other.size,
+                "position" => self.position = other.position,
+                "monitor" => self.monitor = other.monitor.clone(),
+                "follow" => self.follow = other.follow,
+                "fullscreen" => self.fullscreen = other.fullscreen,
+                "split_direction" => self.split_direction = other.split_direction,
+                "remove_title_bar" => self.remove_title_bar = other.remove_title_bar,
+                "on_manage" => self.on_manage = other.on_manage,
+                _ => continue,
+            }
+
+            if !self.keys.contains(key) {
+                self.keys.push(key.clone());
+            }
         }
     }
+
+    /// Returns the name and the value of every setting
+    pub fn settings(&self) -> Vec<(&'static str, String)> {
+        let on_manage = self
+            .on_manage
+            .and_then(|idx| CALLBACKS.lock().unwrap().get(idx).cloned())
+            .map(|fp| fp.fn_name().to_string());
+
+        vec![
+            ("manage", self.manage.to_string()),
+            ("workspace_id", self.workspace_id.to_string()),
+            ("has_custom_titlebar", self.has_custom_titlebar.to_string()),
+            ("chromium", self.chromium.to_string()),
+            ("firefox", self.firefox.to_string()),
+            ("floating", self.floating.to_string()),
+            ("size", format!("{:?}", self.size)),
+            ("position", format!("{:?}", self.position)),
+            ("monitor", format!("{:?}", self.monitor)),
+            ("follow", self.follow.to_string()),
+            ("fullscreen", self.fullscreen.to_string()),
+            ("split_direction", format!("{:?}", self.split_direction)),
+            ("remove_title_bar", format!("{:?}", self.remove_title_bar)),
+            ("on_manage", format!("{:?}", on_manage)),
+        ]
+    }
 }
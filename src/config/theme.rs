@@ -0,0 +1,90 @@
+use crate::util;
+use serde::Serialize;
+
+/// The names of the colors of a theme, which can be used by components instead of a color
+pub const SLOTS: &[&str] = &[
+    "background",
+    "foreground",
+    "accent",
+    "urgent",
+    "active_workspace",
+    "inactive_workspace",
+    "border",
+    "focused_border",
+];
+
+/// A named color palette. Every color is stored as 0x00BBGGRR, which is the layout windows expects.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Theme {
+    pub name: String,
+    pub background: i32,
+    pub foreground: i32,
+    pub accent: i32,
+    pub urgent: i32,
+    pub active_workspace: i32,
+    pub inactive_workspace: i32,
+    /// Only set if the theme sets it explicitly, otherwise windows keeps its default border
+    pub border: Option<i32>,
+    pub focused_border: Option<i32>,
+}
+
+impl Theme {
+    /// Derives every color of the palette from the background, like the bar did before themes
+    /// existed.
+    pub fn derive(name: &str, background: i32, light: bool) -> Self {
+        let (foreground, active_workspace, inactive_workspace) = if light {
+            (
+                0x00333333,
+                util::scale_color(background, 0.75),
+                util::scale_color(background, 0.9),
+            )
+        } else {
+            (
+                0x00ffffff,
+                util::scale_color(background, 2.0),
+                util::scale_color(background, 1.5),
+            )
+        };
+
+        Self {
+            name: name.into(),
+            background,
+            foreground,
+            accent: active_workspace,
+            urgent: 0x003c3cbf,
+            active_workspace,
+            inactive_workspace,
+            border: None,
+            focused_border: None,
+        }
+    }
+
+    pub fn get_slot(&self, slot: &str) -> Option<i32> {
+        match slot {
+            "background" => Some(self.background),
+            "foreground" => Some(self.foreground),
+            "accent" => Some(self.accent),
+            "urgent" => Some(self.urgent),
+            "active_workspace" => Some(self.active_workspace),
+            "inactive_workspace" => Some(self.inactive_workspace),
+            // components can still use the borders when windows keeps its default
+            "border" => Some(self.border.unwrap_or(self.inactive_workspace)),
+            "focused_border" => Some(self.focused_border.unwrap_or(self.active_workspace)),
+            _ => None,
+        }
+    }
+
+    pub fn set_slot(&mut self, slot: &str, color: i32) {
+        match slot {
+            "background" => self.background = color,
+            "foreground" => self.foreground = color,
+            "accent" => self.accent = color,
+            "urgent" => self.urgent = color,
+            "active_workspace" => self.active_workspace = color,
+            "inactive_workspace" => self.inactive_workspace = color,
+            "border" => self.border = Some(color),
+            "focused_border" => self.focused_border = Some(color),
+            _ => {}
+        }
+    }
+}
@@ -1,9 +1,10 @@
 use repository::Repository;
 use semantic_version::SemanticVersion;
+use serde::Serialize;
 
 pub mod repository;
 pub mod semantic_version;
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Serialize)]
 pub struct UpdateChannel {
     pub name: String,
     pub repo: Repository,
@@ -1,5 +1,6 @@
+use serde::Serialize;
 use std::str::FromStr;
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 pub struct SemanticVersion {
     pub major: i32,
     pub minor: i32,
@@ -1,15 +1,35 @@
-#[derive(Debug, Clone)]
+#[cfg(windows)]
+use crate::display::{get_display_by_selector, Display};
+use monitor_selector::MonitorSelector;
+use serde::Serialize;
+
+pub mod monitor_selector;
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct WorkspaceSetting {
     pub id: i32,
-    pub monitor: i32,
+    /// The monitors this workspace is pinned to, ordered by preference.
+    /// The first monitor that is currently connected gets used.
+    pub monitors: Vec<MonitorSelector>,
     pub text: String,
 }
 
+#[cfg(windows)]
+impl WorkspaceSetting {
+    /// Returns the first display of the fallback chain that is currently available and managed
+    pub fn get_display(&self) -> Option<Display> {
+        self.monitors
+            .iter()
+            .filter_map(get_display_by_selector)
+            .find(|d| d.work_mode)
+    }
+}
+
 impl Default for WorkspaceSetting {
     fn default() -> Self {
         Self {
             id: -1,
-            monitor: -1,
+            monitors: Vec::new(),
             text: "".into(),
         }
     }
@@ -0,0 +1,33 @@
+use crate::direction::Direction;
+use serde::Serialize;
+
+/// Describes which monitor a workspace should reside on.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub enum MonitorSelector {
+    /// Index of the monitor counted from left to right and from top to bottom
+    Index(i32),
+    /// Device name of the monitor (e.g. `\\.\DISPLAY2`)
+    Name(String),
+    /// The monitor that is furthest in the given direction
+    Position(Direction),
+    Primary,
+}
+
+impl From<i32> for MonitorSelector {
+    fn from(idx: i32) -> Self {
+        Self::Index(idx)
+    }
+}
+
+impl From<String> for MonitorSelector {
+    fn from(s: String) -> Self {
+        match s.to_lowercase().as_str() {
+            "primary" => Self::Primary,
+            "left" => Self::Position(Direction::Left),
+            "right" => Self::Position(Direction::Right),
+            "top" | "up" => Self::Position(Direction::Up),
+            "bottom" | "down" => Self::Position(Direction::Down),
+            _ => Self::Name(s),
+        }
+    }
+}
@@ -1,4 +1,6 @@
-#[derive(Clone, Copy, EnumString, PartialEq, Debug)]
+use serde::Serialize;
+
+#[derive(Clone, Copy, EnumString, PartialEq, Debug, Serialize)]
 pub enum Direction {
     Left,
     Right,
@@ -1,25 +1,51 @@
+#[cfg(windows)]
+use crate::config::workspace_setting::monitor_selector::MonitorSelector;
+#[cfg(windows)]
+use crate::config::Config;
+#[cfg(windows)]
+use crate::direction::Direction;
+#[cfg(windows)]
 use crate::task_bar;
+#[cfg(windows)]
 use crate::CONFIG;
+#[cfg(windows)]
 use crate::DISPLAYS;
+#[cfg(windows)]
 use std::cmp::Ordering;
+#[cfg(windows)]
 use winapi::shared::minwindef::BOOL;
+#[cfg(windows)]
 use winapi::shared::minwindef::LPARAM;
+#[cfg(windows)]
 use winapi::shared::windef::{HDC, HMONITOR, LPRECT, RECT};
-use winapi::um::shellscalingapi::{GetDpiForMonitor, MDT_RAW_DPI};
-use winapi::um::winuser::EnumDisplayMonitors;
+#[cfg(windows)]
+use winapi::um::shellscalingapi::{GetDpiForMonitor, MDT_EFFECTIVE_DPI};
+#[cfg(windows)]
+use winapi::um::winuser::{
+    EnumDisplayMonitors, GetMonitorInfoW, GetSystemMetrics, MONITORINFO, MONITORINFOEXW,
+    MONITORINFOF_PRIMARY, SM_CMONITORS,
+};
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone)]
 pub struct Display {
     pub hmonitor: i32,
+    /// The device name of the monitor (e.g. `\\.\DISPLAY1`).
+    ///
+    /// Unlike the hmonitor this stays the same when the monitors get enumerated again.
+    pub name: String,
     pub dpi: u32,
     pub is_primary: bool,
+    /// Whether nog manages windows on this display
+    pub work_mode: bool,
     pub left: i32,
     pub right: i32,
     pub top: i32,
     pub bottom: i32,
+    #[cfg(windows)]
     pub task_bar: Option<task_bar::TaskBar>,
 }
 
+#[cfg(windows)]
 impl Display {
     pub fn height(&self) -> i32 {
         self.bottom - self.top
@@ -78,88 +104,272 @@ impl Display {
         };
         self.left + offset
     }
+    /// Converts a value in logical units into pixels of this display.
+    pub fn scale(&self, value: i32) -> i32 {
+        scale_by_dpi(value, self.dpi, CONFIG.lock().unwrap().dpi_scaling)
+    }
+    /// Whether both displays describe the same monitor with the same geometry
+    pub fn is_same(&self, other: &Display) -> bool {
+        self.name == other.name
+            && self.dpi == other.dpi
+            && self.left == other.left
+            && self.right == other.right
+            && self.top == other.top
+            && self.bottom == other.bottom
+    }
     pub fn new(hmonitor: HMONITOR, rect: RECT) -> Self {
         let mut display = Display::default();
         let config = CONFIG.lock().unwrap();
         let mut dpi_x: u32 = 0;
         let mut dpi_y: u32 = 0;
+        let mut monitor_info = MONITORINFOEXW::default();
+
+        monitor_info.cbSize = std::mem::size_of::<MONITORINFOEXW>() as u32;
 
         unsafe {
-            GetDpiForMonitor(hmonitor, MDT_RAW_DPI, &mut dpi_x, &mut dpi_y);
+            GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y);
+            GetMonitorInfoW(
+                hmonitor,
+                &mut monitor_info as *mut MONITORINFOEXW as *mut MONITORINFO,
+            );
         }
         display.dpi = dpi_x;
         display.hmonitor = hmonitor as i32;
+        display.name = String::from_utf16_lossy(
+            &monitor_info
+                .szDevice
+                .iter()
+                .cloned()
+                .take_while(|c| *c != 0)
+                .collect::<Vec<u16>>(),
+        );
         display.left = rect.left;
         display.right = rect.right;
         display.top = rect.top;
         display.bottom = rect.bottom;
 
-        display.is_primary = display.left == 0 && display.top == 0;
+        display.is_primary = monitor_info.dwFlags & MONITORINFOF_PRIMARY != 0;
+        display.work_mode = config.multi_monitor || display.is_primary;
 
         if config.display_app_bar {
-            display.bottom -= config.bar.height;
+            display.bottom -= scale_by_dpi(config.bar.height, display.dpi, config.dpi_scaling);
         }
 
         display
     }
 }
 
-unsafe extern "system" fn monitor_cb(hmonitor: HMONITOR, _: HDC, rect: LPRECT, _: LPARAM) -> BOOL {
-    let display = Display::new(hmonitor, *rect);
-
-    if CONFIG.lock().unwrap().multi_monitor || display.is_primary {
-        DISPLAYS.lock().unwrap().push(display);
+/// Scales the value from 96 dpi (100%) to the given dpi. Returns the value unchanged when scaling is disabled.
+#[cfg(windows)]
+pub fn scale_by_dpi(value: i32, dpi: u32, enabled: bool) -> i32 {
+    if !enabled || dpi == 0 {
+        return value;
     }
 
+    (value as f32 * dpi as f32 / 96.0).round() as i32
+}
+
+/// Returns the number of connected displays. Unlike `DISPLAYS` this also works before the displays got initialized.
+#[cfg(windows)]
+pub fn count() -> i32 {
+    unsafe { GetSystemMetrics(SM_CMONITORS) }
+}
+
+/// There is no window manager running on other platforms, so the config gets evaluated as if
+/// there was a single display
+#[cfg(not(windows))]
+pub fn count() -> i32 {
     1
 }
 
+/// Receives a pointer to the `Vec` the displays get collected in as `LPARAM`
+#[cfg(windows)]
+unsafe extern "system" fn monitor_cb(
+    hmonitor: HMONITOR,
+    _: HDC,
+    rect: LPRECT,
+    displays: LPARAM,
+) -> BOOL {
+    let displays = &mut *(displays as *mut Vec<Display>);
+
+    displays.push(Display::new(hmonitor, *rect));
+
+    1
+}
+
+#[cfg(windows)]
 pub fn init() {
+    *DISPLAYS.lock().unwrap() = enumerate();
+    task_bar::update_task_bars();
+}
+
+/// Returns the connected monitors with the monitor settings applied.
+///
+/// The displays get collected in a local `Vec`, so `DISPLAYS` never contains a partial list while
+/// the monitors get enumerated.
+#[cfg(windows)]
+fn enumerate() -> Vec<Display> {
+    let mut displays: Vec<Display> = Vec::new();
+
     unsafe {
         //is synchronous so don't have to worry about race conditions
         EnumDisplayMonitors(
             std::ptr::null_mut(),
             std::ptr::null_mut(),
             Some(monitor_cb),
-            0,
+            &mut displays as *mut Vec<Display> as LPARAM,
         );
     }
 
-    {
-        let mut displays = DISPLAYS.lock().unwrap();
+    displays.sort_by(|x, y| {
+        let ordering = y.left.cmp(&x.left);
 
-        displays.sort_by(|x, y| {
-            let ordering = y.left.cmp(&x.left);
+        if ordering == Ordering::Equal {
+            return y.top.cmp(&x.top);
+        }
+
+        ordering
+    });
+
+    let config = CONFIG.lock().unwrap().clone();
+
+    for (hmonitor, work_mode) in configured_work_modes(&config, &displays) {
+        if let Some(d) = displays.iter_mut().find(|d| d.hmonitor == hmonitor) {
+            d.work_mode = work_mode;
+        }
+    }
+
+    displays
+}
+
+/// Returns the hmonitor of every display together with the work mode the config gives it
+#[cfg(windows)]
+pub fn get_configured_work_modes(config: &Config) -> Vec<(i32, bool)> {
+    let displays = DISPLAYS.lock().unwrap();
+
+    configured_work_modes(config, &displays)
+}
+
+#[cfg(windows)]
+fn configured_work_modes(config: &Config, displays: &[Display]) -> Vec<(i32, bool)> {
+    let mut work_modes: Vec<(i32, bool)> = displays
+        .iter()
+        .map(|d| (d.hmonitor, config.multi_monitor || d.is_primary))
+        .collect();
 
-            if ordering == Ordering::Equal {
-                return y.top.cmp(&x.top);
+    for setting in &config.monitor_settings {
+        if let Some(display) = select_display(displays, &setting.monitor) {
+            if let Some((_, work_mode)) = work_modes
+                .iter_mut()
+                .find(|(hmonitor, _)| *hmonitor == display.hmonitor)
+            {
+                *work_mode = setting.work_mode;
             }
+        }
+    }
+
+    work_modes
+}
 
-            ordering
-        });
+/// Enumerates the monitors again and returns whether the display setup changed.
+#[cfg(windows)]
+pub fn refresh() -> bool {
+    let mut displays = enumerate();
+
+    let changed = {
+        let mut current = DISPLAYS.lock().unwrap();
+
+        // keep the work mode that got toggled at runtime
+        for display in displays.iter_mut() {
+            if let Some(old) = current.iter().find(|d| d.name == display.name) {
+                display.work_mode = old.work_mode;
+                display.task_bar = old.task_bar;
+            }
+        }
+
+        let changed = current.len() != displays.len()
+            || current
+                .iter()
+                .zip(displays.iter())
+                .any(|(old, new)| !old.is_same(new));
+
+        *current = displays;
+
+        changed
+    };
+
+    if changed {
+        task_bar::update_task_bars();
     }
 
-    task_bar::update_task_bars();
+    changed
 }
 
+#[cfg(windows)]
 pub fn get_primary_display() -> Display {
-    *DISPLAYS
+    DISPLAYS
         .lock()
         .unwrap()
         .iter()
         .find(|d| d.is_primary)
+        .cloned()
         .expect("Couldn't find primary display")
 }
 
-pub fn get_display_by_hmonitor(hmonitor: i32) -> Display {
-    *DISPLAYS
+#[cfg(windows)]
+pub fn get_display_by_hmonitor(hmonitor: i32) -> Option<Display> {
+    DISPLAYS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|d| d.hmonitor == hmonitor)
+        .cloned()
+}
+
+/// Returns the display with the given hmonitor. The hmonitor can be stale after the display setup
+/// changed, in which case the managed display or the primary display gets returned instead.
+#[cfg(windows)]
+pub fn get_display_by_hmonitor_or_fallback(hmonitor: i32) -> Display {
+    get_display_by_hmonitor(hmonitor)
+        .or_else(get_managed_display)
+        .unwrap_or_else(get_primary_display)
+}
+
+/// Whether nog manages windows on the display
+#[cfg(windows)]
+pub fn is_managed(hmonitor: i32) -> bool {
+    DISPLAYS
         .lock()
         .unwrap()
         .iter()
         .find(|d| d.hmonitor == hmonitor)
-        .expect(format!("Couldn't find display with hmonitor of {}", hmonitor).as_str())
+        .map(|d| d.work_mode)
+        .unwrap_or(false)
 }
 
+/// Returns the first display which is managed by nog, preferring the primary display
+#[cfg(windows)]
+pub fn get_managed_display() -> Option<Display> {
+    let displays = DISPLAYS.lock().unwrap();
+
+    displays
+        .iter()
+        .find(|d| d.is_primary && d.work_mode)
+        .or_else(|| displays.iter().find(|d| d.work_mode))
+        .cloned()
+}
+
+#[cfg(windows)]
+pub fn get_display_by_name(name: &str) -> Option<Display> {
+    DISPLAYS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|d| d.name == name)
+        .cloned()
+}
+
+#[cfg(windows)]
 pub fn get_display_by_idx(idx: i32) -> Display {
     let displays = DISPLAYS.lock().unwrap();
 
@@ -169,7 +379,34 @@ pub fn get_display_by_idx(idx: i32) -> Display {
         std::cmp::max(displays.len() - (idx as usize), 0)
     };
 
-    *displays
+    displays
         .get(x)
+        .cloned()
         .expect(format!("Couldn't get display at index {}", x).as_str())
 }
+
+#[cfg(windows)]
+pub fn get_display_by_selector(selector: &MonitorSelector) -> Option<Display> {
+    select_display(&DISPLAYS.lock().unwrap(), selector).cloned()
+}
+
+#[cfg(windows)]
+fn select_display<'a>(displays: &'a [Display], selector: &MonitorSelector) -> Option<&'a Display> {
+    match selector {
+        MonitorSelector::Index(idx) => {
+            if *idx < 1 || *idx as usize > displays.len() {
+                None
+            } else {
+                displays.get(displays.len() - *idx as usize)
+            }
+        }
+        MonitorSelector::Name(name) => displays.iter().find(|d| d.name == *name),
+        MonitorSelector::Primary => displays.iter().find(|d| d.is_primary),
+        MonitorSelector::Position(direction) => match direction {
+            Direction::Left => displays.iter().min_by_key(|d| d.left),
+            Direction::Right => displays.iter().max_by_key(|d| d.right),
+            Direction::Up => displays.iter().min_by_key(|d| d.top),
+            Direction::Down => displays.iter().max_by_key(|d| d.bottom),
+        },
+    }
+}
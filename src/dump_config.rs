@@ -0,0 +1,48 @@
+use crate::{
+    config::{location, rhai::engine::parse_config, Config},
+    CONFIG,
+};
+use std::path::PathBuf;
+
+fn to_json(config: &Config) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(config)
+}
+
+/// Writes the config that is currently in use into the config folder and returns the path of the file.
+pub fn write() -> Result<PathBuf, Box<dyn std::error::Error>> {
+    // serializing callbacks needs other locks, so the config shouldn't stay locked
+    let config = CONFIG.lock().unwrap().clone();
+    let mut path = location::get_config_dir();
+
+    path.push("effective_config.json");
+
+    std::fs::write(&path, to_json(&config)?)?;
+
+    Ok(path)
+}
+
+/// Evaluates the config without starting the window manager, prints it as json and returns the exit code.
+pub fn run() -> i32 {
+    match parse_config() {
+        Ok((config, diagnostics)) => {
+            for diagnostic in diagnostics {
+                eprintln!("{}", diagnostic);
+            }
+
+            match to_json(&config) {
+                Ok(json) => {
+                    println!("{}", json);
+                    0
+                }
+                Err(e) => {
+                    eprintln!("Failed to serialize the config: {}", e);
+                    1
+                }
+            }
+        }
+        Err(diagnostic) => {
+            eprintln!("{}", diagnostic);
+            1
+        }
+    }
+}
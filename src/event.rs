@@ -1,14 +1,32 @@
-use crate::{keybindings::keybinding::Keybinding, win_event_handler::win_event::WinEvent};
+use crate::keybindings::{keybinding::Keybinding, keybinding_type::KeybindingType};
+#[cfg(windows)]
+use crate::win_event_handler::win_event::WinEvent;
 use crossbeam_channel::unbounded;
 use crossbeam_channel::Receiver;
 use crossbeam_channel::Sender;
+use rhai::{Dynamic, FnPtr};
 
+// the events only get handled by the main loop of the window manager, which doesn't run on other
+// platforms
+#[cfg_attr(not(windows), allow(dead_code))]
 #[derive(Debug, Clone)]
 pub enum Event {
     Keybinding(Keybinding),
+    /// Executes an action that got dispatched by a script
+    Action(KeybindingType),
+    #[cfg(windows)]
     WinEvent(WinEvent),
     RedrawAppBar,
+    DisplayChange,
     ReloadConfig,
+    /// Applies the side effects of the config changes that scripts made at runtime
+    ConfigChanged,
+    /// Calls the callbacks of a config hook with the arguments
+    Hook(String, Vec<Dynamic>),
+    /// Calls the callback of the timer with the id
+    Timer(i32),
+    /// Calls the function with the arguments
+    Callback(FnPtr, Vec<Dynamic>),
     Exit,
 }
 
@@ -17,6 +35,7 @@ pub type EventReceiver = Receiver<Event>;
 
 pub struct EventChannel {
     pub sender: EventSender,
+    #[cfg_attr(not(windows), allow(dead_code))]
     pub receiver: EventReceiver,
 }
 
@@ -1,2 +1,3 @@
+pub mod display_change;
 pub mod keybinding;
 pub mod winevent;
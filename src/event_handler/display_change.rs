@@ -0,0 +1,110 @@
+use crate::{
+    bar,
+    display::{self, get_display_by_name, get_primary_display},
+    CONFIG, DISPLAYS, GRIDS, VISIBLE_WORKSPACES, WORKSPACE_ID, WORK_MODE,
+};
+use log::{debug, info};
+
+pub fn handle() -> Result<(), Box<dyn std::error::Error>> {
+    if !display::refresh() {
+        debug!("Display setup didn't change");
+        return Ok(());
+    }
+
+    info!("Display setup changed. Updating workspaces");
+
+    let (workspace_settings, display_app_bar) = {
+        let config = CONFIG.lock().unwrap();
+
+        (config.workspace_settings.clone(), config.display_app_bar)
+    };
+
+    let mut grids = GRIDS.lock().unwrap();
+    let mut visible_workspaces = VISIBLE_WORKSPACES.lock().unwrap();
+    let previously_visible: Vec<i32> = visible_workspaces.values().cloned().collect();
+
+    visible_workspaces.clear();
+
+    for display in DISPLAYS.lock().unwrap().iter() {
+        visible_workspaces.insert(display.hmonitor, 0);
+    }
+
+    for grid in grids.iter_mut() {
+        if let Some(display) = workspace_settings
+            .iter()
+            .find(|s| s.id == grid.id)
+            .and_then(|s| s.get_display())
+        {
+            grid.displaced_from = None;
+            grid.display = display;
+        } else if let Some(display) = grid
+            .displaced_from
+            .clone()
+            .and_then(|name| get_display_by_name(&name))
+        {
+            debug!(
+                "Moving workspace {} back to display {}",
+                grid.id, display.name
+            );
+            grid.displaced_from = None;
+            grid.display = display;
+        } else if let Some(display) = get_display_by_name(&grid.display.name) {
+            grid.display = display;
+        } else {
+            let display = get_primary_display();
+
+            debug!(
+                "Display {} got removed. Moving workspace {} to display {}",
+                grid.display.name, grid.id, display.name
+            );
+
+            if grid.displaced_from.is_none() {
+                grid.displaced_from = Some(grid.display.name.clone());
+            }
+
+            grid.display = display;
+        }
+    }
+
+    let mut grids_to_show: Vec<_> = grids
+        .iter()
+        .filter(|g| previously_visible.contains(&g.id))
+        .collect();
+
+    // grids that stayed on their display keep priority over grids that got moved
+    grids_to_show.sort_by_key(|g| g.displaced_from.is_some());
+
+    for grid in grids_to_show {
+        let visible_id = visible_workspaces
+            .get(&grid.display.hmonitor)
+            .cloned()
+            .unwrap_or_default();
+
+        if visible_id == 0 {
+            visible_workspaces.insert(grid.display.hmonitor, grid.id);
+            grid.draw_grid();
+            grid.show();
+        } else {
+            grid.hide();
+        }
+    }
+
+    let mut workspace_id = WORKSPACE_ID.lock().unwrap();
+
+    if !visible_workspaces.values().any(|id| *id == *workspace_id) {
+        if let Some(id) = visible_workspaces.values().find(|id| **id != 0) {
+            *workspace_id = *id;
+        }
+    }
+
+    drop(workspace_id);
+    drop(visible_workspaces);
+    drop(grids);
+
+    if *WORK_MODE.lock().unwrap() && display_app_bar {
+        bar::close::close();
+        bar::create::create()?;
+    }
+
+    Ok(())
+}
@@ -1,7 +1,8 @@
 use crate::{
-    config::{rhai::engine, rule::Rule},
-    display::get_display_by_idx,
+    config::{rhai::engine, rule::Rule, workspace_setting::monitor_selector::MonitorSelector},
+    display::{get_display_by_idx, get_display_by_selector},
     event::Event,
+    event_handler::winevent::name_change,
     hot_reload::update_config,
     keybindings::{self, keybinding::Keybinding, keybinding_type::KeybindingType},
     with_current_grid, with_grid_by_id,
@@ -12,23 +13,35 @@ use log::{debug, error, info};
 use winapi::um::processthreadsapi::{CreateProcessA, PROCESS_INFORMATION, STARTUPINFOA};
 
 mod close_tile;
+mod explain_window;
 mod focus;
 mod resize;
 mod split;
 mod swap;
+mod switch_profile;
 mod toggle_floating_mode;
+pub mod toggle_monitor_work_mode;
 pub mod toggle_work_mode;
 
 pub fn handle(kb: Keybinding) -> Result<(), Box<dyn std::error::Error>> {
-    if let KeybindingType::MoveWorkspaceToMonitor(_) = kb.typ {
-        if !CONFIG.lock().unwrap().multi_monitor {
+    info!("Received keybinding of type {:?}", kb.typ);
+
+    execute(kb.typ)
+}
+
+/// Executes the action, no matter whether it was triggered by a keybinding or by a script
+pub fn execute(typ: KeybindingType) -> Result<(), Box<dyn std::error::Error>> {
+    if let KeybindingType::MoveWorkspaceToMonitor(monitor) = typ {
+        let work_mode = get_display_by_selector(&MonitorSelector::Index(monitor))
+            .map_or(false, |d| d.work_mode);
+
+        if !CONFIG.lock().unwrap().multi_monitor || !work_mode {
             return Ok(());
         }
     }
 
-    info!("Received keybinding of type {:?}", kb.typ);
     let sender = CHANNEL.sender.clone();
-    match kb.typ {
+    match typ {
         KeybindingType::Launch(cmd) => {
             let mut si = STARTUPINFOA::default();
             let mut pi = PROCESS_INFORMATION::default();
@@ -83,6 +96,7 @@ pub fn handle(kb: Keybinding) -> Result<(), Box<dyn std::error::Error>> {
                     tile.window.reset();
 
                     grid.close_tile_by_window_id(id);
+                    name_change::forget(id);
                 }
             });
         }
@@ -119,19 +133,35 @@ pub fn handle(kb: Keybinding) -> Result<(), Box<dyn std::error::Error>> {
             }
         }
         KeybindingType::ToggleWorkMode => toggle_work_mode::handle()?,
+        KeybindingType::ToggleMonitorWorkMode => toggle_monitor_work_mode::handle()?,
+        KeybindingType::SwitchProfile(profile) => switch_profile::handle(profile)?,
+        KeybindingType::SwitchTheme(name) => {
+            let mut current_config = CONFIG.lock().unwrap().clone();
+
+            // "default" goes back to the colors that get derived from the bar color
+            if name == "default" {
+                current_config.active_theme = None;
+            } else if current_config.themes.iter().any(|t| t.name == name) {
+                current_config.active_theme = Some(name);
+            } else {
+                return Err(format!("unknown theme {}", name).into());
+            }
+
+            update_config(current_config)?;
+        }
         KeybindingType::IncrementConfig(field, value) => {
             let mut current_config = CONFIG.lock().unwrap().clone();
-            current_config.increment_field(&field, value);
+            current_config.increment_field(&field, value)?;
             update_config(current_config)?;
         }
         KeybindingType::DecrementConfig(field, value) => {
             let mut current_config = CONFIG.lock().unwrap().clone();
-            current_config.decrement_field(&field, value);
+            current_config.decrement_field(&field, value)?;
             update_config(current_config)?;
         }
         KeybindingType::ToggleConfig(field) => {
             let mut current_config = CONFIG.lock().unwrap().clone();
-            current_config.toggle_field(&field);
+            current_config.toggle_field(&field)?;
             update_config(current_config)?;
         }
         KeybindingType::Resize(direction, amount) => resize::handle(direction, amount)?,
@@ -168,6 +198,12 @@ pub fn handle(kb: Keybinding) -> Result<(), Box<dyn std::error::Error>> {
             });
         }
         KeybindingType::Callback(idx) => engine::call(idx),
+        KeybindingType::ExplainWindow => explain_window::handle()?,
+        KeybindingType::Sequence(actions) => {
+            for action in actions {
+                execute(action)?;
+            }
+        }
         KeybindingType::IgnoreTile => {
             with_current_grid(|grid| {
                 if let Some(tile) = grid.get_focused_tile() {
@@ -178,8 +214,10 @@ pub fn handle(kb: Keybinding) -> Result<(), Box<dyn std::error::Error>> {
 
                     debug!("Adding rule with pattern {}", pattern);
 
-                    rule.pattern = regex::Regex::new(&pattern).expect("Failed to build regex");
+                    rule.matcher.process =
+                        Some(regex::Regex::new(&pattern).expect("Failed to build regex"));
                     rule.manage = false;
+                    rule.keys = vec!["manage".into()];
 
                     rules.push(rule);
                 }
@@ -0,0 +1,70 @@
+use crate::{
+    config::rule::{Rule, WindowProperties},
+    event_handler::winevent::show,
+    popup::Popup,
+    util,
+    window::Window,
+    CONFIG,
+};
+
+/// Shows a popup with the rules that match the focused window and the settings that result
+/// from them.
+pub fn handle() -> Result<(), Box<dyn std::error::Error>> {
+    let hwnd = Window::get_foreground_window()?;
+    let mut window = Window::new(hwnd as i32);
+
+    window.title = util::get_title_of_window(hwnd)?;
+
+    let properties = WindowProperties::new(&window);
+    let rules = show::find_matching_rules(&properties);
+    let rule = show::find_rule_for(&properties).unwrap_or_default();
+    let merge_rules = CONFIG.lock().unwrap().merge_rules;
+
+    let mut text = vec![
+        format!("Title: {}", properties.title),
+        format!("Process: {}", properties.process_name),
+        format!("Path: {}", properties.process_path),
+        format!("Class: {}", properties.class_name),
+        String::new(),
+        format!("Matching rules ({}):", rules.len()),
+    ];
+
+    for matched in &rules {
+        text.push(format!(
+            "  [priority {}{}] {}",
+            matched.priority,
+            if matched.is_override {
+                ", override"
+            } else {
+                ""
+            },
+            matched.matcher
+        ));
+    }
+
+    text.push(String::new());
+    text.push(format!(
+        "Effective settings ({}):",
+        if merge_rules { "merged" } else { "first match" }
+    ));
+
+    let defaults = Rule::default().settings();
+
+    for (i, (key, value)) in rule.settings().into_iter().enumerate() {
+        // settings that weren't changed by a rule are marked as defaults
+        let suffix = if defaults[i].1 == value && !rule.keys.iter().any(|k| k == key) {
+            " (default)"
+        } else {
+            ""
+        };
+
+        text.push(format!("  {}: {}{}", key, value, suffix));
+    }
+
+    Popup::new()
+        .with_text(&text.iter().map(|t| t.as_str()).collect::<Vec<&str>>())
+        .with_padding(5)
+        .create();
+
+    Ok(())
+}
@@ -0,0 +1,32 @@
+use crate::{config::location, hot_reload, startup, CONFIG};
+use log::info;
+
+pub fn handle(profile: String) -> Result<(), Box<dyn std::error::Error>> {
+    let prev_profile = location::get_profile().unwrap_or_else(|| "default".into());
+
+    location::set_profile(&profile)?;
+
+    let config_file = location::get_config_file();
+
+    if !config_file.exists() {
+        location::set_profile(&prev_profile)?;
+        return Err(format!("Profile {} doesn't exist ({:?})", profile, config_file).into());
+    }
+
+    info!("Switching to profile {}", profile);
+
+    // the previous profile stays active if the config of the new one is broken, so a later
+    // reload doesn't pick up the broken file
+    if !hot_reload::reload_config()? {
+        location::set_profile(&prev_profile)?;
+        return Err(format!("Failed to load profile {}", profile).into());
+    }
+
+    // the startup entry contains the profile, so nog starts with the new one next time
+    let launch_on_startup = CONFIG.lock().unwrap().launch_on_startup;
+    if launch_on_startup {
+        startup::set_launch_on_startup(true)?;
+    }
+
+    Ok(())
+}
@@ -1,4 +1,5 @@
 use crate::event::Event;
+use crate::event_handler::winevent::name_change;
 use crate::window::Window;
 use crate::CHANNEL;
 use crate::GRIDS;
@@ -45,6 +46,8 @@ pub fn handle() -> Result<(), Box<dyn std::error::Error>> {
 
             grid.close_tile_by_window_id(focused_tile_id);
             grid.draw_grid();
+            // a title change shouldn't manage the window again
+            name_change::forget(focused_tile_id);
         }
     } else {
         CHANNEL.sender.clone().send(Event::WinEvent(WinEvent {
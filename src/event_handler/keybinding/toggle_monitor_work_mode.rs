@@ -0,0 +1,136 @@
+use crate::{
+    bar,
+    display::{get_display_by_hmonitor, is_managed},
+    event::Event,
+    event_handler::winevent::name_change,
+    task_bar,
+    win_event_handler::{win_event::WinEvent, win_event_type::WinEventType},
+    workspace::change_workspace,
+    CHANNEL, CONFIG, DISPLAYS, GRIDS, VISIBLE_WORKSPACES,
+};
+use log::info;
+use winapi::shared::minwindef::{BOOL, LPARAM};
+use winapi::shared::windef::{HWND, POINT};
+use winapi::um::winuser::{
+    EnumWindows, GetCursorPos, IsWindowVisible, MonitorFromPoint, MonitorFromWindow,
+    MONITOR_DEFAULTTONEAREST, MONITOR_DEFAULTTONULL,
+};
+
+/// Toggles the work mode of the display the cursor is on
+pub fn handle() -> Result<(), Box<dyn std::error::Error>> {
+    let hmonitor = unsafe {
+        let mut point = POINT::default();
+        GetCursorPos(&mut point);
+        MonitorFromPoint(point, MONITOR_DEFAULTTONEAREST) as i32
+    };
+
+    set_work_mode(hmonitor, !is_managed(hmonitor))
+}
+
+/// Turns the work mode of the display on or off
+pub fn set_work_mode(hmonitor: i32, work_mode: bool) -> Result<(), Box<dyn std::error::Error>> {
+    let (display_app_bar, remove_task_bar) = {
+        let config = CONFIG.lock().unwrap();
+
+        (config.display_app_bar, config.remove_task_bar)
+    };
+
+    {
+        let mut displays = DISPLAYS.lock().unwrap();
+        let display = displays
+            .iter_mut()
+            .find(|d| d.hmonitor == hmonitor)
+            .ok_or("Couldn't find the display")?;
+
+        display.work_mode = work_mode;
+
+        info!(
+            "Turning work mode {} for display {}",
+            if work_mode { "on" } else { "off" },
+            display.name
+        );
+    }
+
+    if work_mode {
+        manage_windows(hmonitor)?;
+    } else {
+        for grid in GRIDS
+            .lock()
+            .unwrap()
+            .iter_mut()
+            .filter(|g| g.display.hmonitor == hmonitor)
+        {
+            for tile in &mut grid.tiles.clone() {
+                grid.close_tile_by_window_id(tile.window.id);
+                name_change::forget(tile.window.id);
+                tile.window.reset();
+            }
+        }
+
+        VISIBLE_WORKSPACES.lock().unwrap().insert(hmonitor, 0);
+    }
+
+    if remove_task_bar {
+        task_bar::set_taskbar_visibility(hmonitor, !work_mode);
+    }
+
+    if display_app_bar {
+        bar::close::close();
+        bar::create::create()?;
+    }
+
+    Ok(())
+}
+
+/// Shows a workspace on the display and manages the windows that are already on it
+fn manage_windows(hmonitor: i32) -> Result<(), Box<dyn std::error::Error>> {
+    let display = get_display_by_hmonitor(hmonitor).ok_or("Couldn't find the display")?;
+    let visible: Vec<i32> = VISIBLE_WORKSPACES
+        .lock()
+        .unwrap()
+        .values()
+        .cloned()
+        .collect();
+
+    let id = {
+        let mut grids = GRIDS.lock().unwrap();
+        // empty workspaces are preferred, because showing a workspace also shows its windows
+        let idx = grids
+            .iter()
+            .position(|g| !visible.contains(&g.id) && g.tiles.is_empty())
+            .or_else(|| grids.iter().position(|g| !visible.contains(&g.id)))
+            .ok_or("There is no workspace left that could be shown on the display")?;
+
+        grids[idx].display = display;
+        grids[idx].id
+    };
+
+    change_workspace(id, true)?;
+
+    // the windows get managed by the main loop, after the workspace became the current one
+    unsafe {
+        EnumWindows(Some(enum_windows_cb), hmonitor as LPARAM);
+    }
+
+    Ok(())
+}
+
+unsafe extern "system" fn enum_windows_cb(hwnd: HWND, hmonitor: LPARAM) -> BOOL {
+    if IsWindowVisible(hwnd) != 0
+        && MonitorFromWindow(hwnd, MONITOR_DEFAULTTONULL) as i32 == hmonitor as i32
+        && bar::get_bar_by_hwnd(hwnd as i32).is_none()
+    {
+        let event = Event::WinEvent(WinEvent {
+            typ: WinEventType::Show(false),
+            hwnd: hwnd as i32,
+        });
+
+        CHANNEL
+            .sender
+            .clone()
+            .send(event)
+            .expect("Failed to send show event");
+    }
+
+    1
+}
@@ -8,7 +8,8 @@ use winapi::shared::windef::HWND;
 
 mod destroy;
 mod focus_change;
-mod show;
+pub mod name_change;
+pub mod show;
 
 pub fn handle(ev: WinEvent) -> Result<(), Box<dyn std::error::Error>> {
     let grids = GRIDS.lock().unwrap();
@@ -25,7 +26,15 @@ pub fn handle(ev: WinEvent) -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-    if title.is_none() && ev.typ != WinEventType::Show(false) && ev.typ != WinEventType::Show(true)
+    // windows that aren't managed can still get managed if they change their title shortly
+    // after they appeared
+    let is_recent_name_change =
+        ev.typ == WinEventType::NameChange && name_change::is_recent(ev.hwnd);
+
+    if title.is_none()
+        && ev.typ != WinEventType::Show(false)
+        && ev.typ != WinEventType::Show(true)
+        && !is_recent_name_change
     {
         return Ok(());
     }
@@ -44,6 +53,7 @@ pub fn handle(ev: WinEvent) -> Result<(), Box<dyn std::error::Error>> {
         WinEventType::Destroy => destroy::handle(ev.hwnd as HWND, grid_id)?,
         WinEventType::Show(ignore) => show::handle(ev.hwnd as HWND, ignore)?,
         WinEventType::FocusChange => focus_change::handle(ev.hwnd as HWND)?,
+        WinEventType::NameChange => name_change::handle(ev.hwnd as HWND, grid_id)?,
         WinEventType::Hide => {}
     };
 
@@ -1,3 +1,4 @@
+use crate::config::rhai::hooks;
 use crate::with_current_grid;
 use winapi::shared::windef::HWND;
 
@@ -8,9 +9,15 @@ pub fn handle(hwnd: HWND) -> Result<(), Box<dyn std::error::Error>> {
                 return Ok(());
             }
 
-            if grid.get_tile_by_id(hwnd as i32).is_some() {
+            if let Some(tile) = grid.get_tile_by_id(hwnd as i32) {
                 grid.focus_stack.clear();
                 grid.focused_window_id = Some(hwnd as i32);
+                grid.update_borders();
+
+                hooks::emit(
+                    "focus_changed",
+                    vec![hooks::window_to_dynamic(&tile.window)],
+                );
             }
         }
 
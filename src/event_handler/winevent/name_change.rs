@@ -0,0 +1,110 @@
+use super::show;
+use crate::{config::rule::Rule, util, window::Window};
+use lazy_static::lazy_static;
+use log::debug;
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+use winapi::shared::windef::HWND;
+
+/// How long after a window appeared a change of its title can still change its rule. Browsers and
+/// editors usually set their final title within the first few seconds.
+const GRACE_PERIOD: Duration = Duration::from_secs(10);
+
+struct RecentWindow {
+    shown_at: Instant,
+    rule: Option<Rule>,
+    /// Whether the window got managed regardless of its rule
+    force: bool,
+}
+
+lazy_static! {
+    /// The windows that appeared within the grace period
+    static ref RECENT_WINDOWS: Mutex<HashMap<i32, RecentWindow>> = Mutex::new(HashMap::new());
+}
+
+/// Remembers the rule of a window that got shown. Showing the window again doesn't extend its
+/// grace period.
+pub fn remember(hwnd: i32, rule: Option<Rule>, force: bool) {
+    let mut windows = RECENT_WINDOWS.lock().unwrap();
+
+    windows.retain(|_, window| window.shown_at.elapsed() < GRACE_PERIOD);
+
+    let shown_at = windows
+        .get(&hwnd)
+        .map(|window| window.shown_at)
+        .unwrap_or_else(Instant::now);
+
+    windows.insert(
+        hwnd,
+        RecentWindow {
+            shown_at,
+            rule,
+            force,
+        },
+    );
+}
+
+/// Forgets the window, so a change of its title doesn't manage it again (e.g. after it got
+/// destroyed or the user unmanaged it)
+pub fn forget(hwnd: i32) {
+    RECENT_WINDOWS.lock().unwrap().remove(&hwnd);
+}
+
+/// Whether a change of the title of the window can still change its rule
+pub fn is_recent(hwnd: i32) -> bool {
+    RECENT_WINDOWS
+        .lock()
+        .unwrap()
+        .get(&hwnd)
+        .map(|window| window.shown_at.elapsed() < GRACE_PERIOD)
+        .unwrap_or(false)
+}
+
+/// Matches the rules again and applies the new rule if a different one matches now.
+///
+/// A managed window only gets the settings applied that differ from its previous rule, so it
+/// isn't handled like a new window. Windows that aren't managed get handled like they just
+/// appeared, in case their new rule manages them.
+pub fn handle(hwnd: HWND, grid_id: Option<i32>) -> Result<(), Box<dyn std::error::Error>> {
+    let (previous_rule, force) = match RECENT_WINDOWS.lock().unwrap().get(&(hwnd as i32)) {
+        Some(window) if window.shown_at.elapsed() < GRACE_PERIOD => {
+            (window.rule.clone(), window.force)
+        }
+        _ => return Ok(()),
+    };
+
+    let mut window = Window::new(hwnd as i32);
+    window.title = util::get_title_of_window(hwnd)?;
+
+    let rule = show::find_rule(&window);
+
+    if rule == previous_rule {
+        return Ok(());
+    }
+
+    debug!(
+        "The rule of '{}' changed after its title changed",
+        window.title
+    );
+
+    if grid_id.is_none() {
+        return show::handle(hwnd, force);
+    }
+
+    remember(hwnd as i32, rule.clone(), force);
+
+    let manages = rule
+        .as_ref()
+        .map(|rule| rule.manage && !rule.floating)
+        .unwrap_or(true);
+
+    // a window that got managed regardless of its rule stays managed
+    if force && !manages {
+        return Ok(());
+    }
+
+    show::update_rule(hwnd as i32, rule)
+}
@@ -1,10 +1,209 @@
+use super::name_change;
 use crate::{
-    util, window::gwl_ex_style::GwlExStyle, window::gwl_style::GwlStyle, window::Window,
-    workspace::change_workspace, ADDITIONAL_RULES, CONFIG, GRIDS, WORKSPACE_ID,
+    config::{
+        rhai::{engine::CALLBACKS, hooks},
+        rule::{Rule, WindowProperties},
+    },
+    display::{get_display_by_hmonitor_or_fallback, get_display_by_selector, is_managed},
+    event::Event,
+    util,
+    window::gwl_ex_style::GwlExStyle,
+    window::gwl_style::GwlStyle,
+    window::Window,
+    workspace::{change_workspace, is_visible_workspace},
+    ADDITIONAL_RULES, CHANNEL, CONFIG, GRIDS, VISIBLE_WORKSPACES, WORKSPACE_ID,
 };
 use log::debug;
 use winapi::shared::windef::HWND;
 
+/// Moves a floating window to the size and position of its rule. Windows that only have a size
+/// get centered on their display.
+fn apply_floating_rule(window: &Window, rule: &Rule) -> Result<(), Box<dyn std::error::Error>> {
+    if rule.size.is_none() && rule.position.is_none() {
+        return Ok(());
+    }
+
+    let display = rule
+        .monitor
+        .as_ref()
+        .and_then(get_display_by_selector)
+        .unwrap_or_else(|| get_display_by_hmonitor_or_fallback(window.get_monitor()));
+    let rect = window.get_rect()?;
+
+    let (width, height) = rule
+        .size
+        .map(|(width, height)| (display.scale(width), display.scale(height)))
+        .unwrap_or((rect.right - rect.left, rect.bottom - rect.top));
+
+    let (x, y) = rule
+        .position
+        .map(|(x, y)| (display.scale(x), display.scale(y)))
+        .unwrap_or((
+            (display.working_area_width() - width) / 2,
+            (display.working_area_height() - height) / 2,
+        ));
+
+    window.set_pos(
+        display.working_area_left() + x,
+        display.working_area_top() + y,
+        width,
+        height,
+    );
+
+    Ok(())
+}
+
+/// Returns every rule that matches the window, where rules with a greater precedence come first.
+/// Rules with the same precedence keep the order they got declared in.
+pub fn find_matching_rules(properties: &WindowProperties) -> Vec<Rule> {
+    let additional_rules = ADDITIONAL_RULES.lock().unwrap();
+
+    let mut rules: Vec<Rule> = CONFIG
+        .lock()
+        .unwrap()
+        .rules
+        .iter()
+        .chain(additional_rules.iter())
+        .filter(|rule| rule.matcher.matches(properties))
+        .cloned()
+        .collect();
+
+    // the sort is stable
+    rules.sort_by(|a, b| b.precedence().cmp(&a.precedence()));
+
+    rules
+}
+
+/// Returns the rule that applies to the window.
+pub fn find_rule(window: &Window) -> Option<Rule> {
+    find_rule_for(&WindowProperties::new(window))
+}
+
+/// Returns the rule that applies to a window with the properties.
+///
+/// Usually that's the first matching rule. With `merge_rules` every matching rule gets merged
+/// instead, where the settings of rules with a greater precedence or that got declared later
+/// override the ones of the others.
+pub fn find_rule_for(properties: &WindowProperties) -> Option<Rule> {
+    let mut rules = find_matching_rules(properties);
+
+    for rule in &rules {
+        debug!("Rule({}) matched!", rule.matcher);
+    }
+
+    if !CONFIG.lock().unwrap().merge_rules {
+        return rules.into_iter().next();
+    }
+
+    rules.sort_by_key(|rule| rule.precedence());
+
+    let mut rules = rules.into_iter();
+    let mut merged = rules.next()?;
+
+    for rule in rules {
+        merged.merge(&rule);
+    }
+
+    Some(merged)
+}
+
+/// Returns the workspace the rule moves the window to
+fn get_target_workspace(rule: &Rule) -> Option<i32> {
+    if rule.workspace_id != -1 {
+        return Some(rule.workspace_id);
+    }
+
+    rule.monitor
+        .as_ref()
+        .and_then(get_display_by_selector)
+        .and_then(|display| {
+            VISIBLE_WORKSPACES
+                .lock()
+                .unwrap()
+                .get(&display.hmonitor)
+                .copied()
+        })
+        // 0 means that no workspace is visible on the monitor
+        .filter(|id| *id != 0)
+}
+
+/// Applies the new rule of a window that is already managed. Only the settings that differ from
+/// its previous rule get applied, so the window doesn't get handled like a new one.
+pub fn update_rule(hwnd: i32, rule: Option<Rule>) -> Result<(), Box<dyn std::error::Error>> {
+    let mut grids = GRIDS.lock().unwrap();
+    let grid_idx = match grids.iter().position(|g| g.get_tile_by_id(hwnd).is_some()) {
+        Some(idx) => idx,
+        None => return Ok(()),
+    };
+    let new_rule = rule.clone().unwrap_or_default();
+
+    if !new_rule.manage || new_rule.floating {
+        let grid = &mut grids[grid_idx];
+
+        if let Some(mut tile) = grid.close_tile_by_window_id(hwnd) {
+            debug!("'{}' isn't managed by its new rule", tile.window.title);
+
+            tile.window.reset();
+            grid.draw_grid();
+            drop(grids);
+
+            if new_rule.floating {
+                return apply_floating_rule(&tile.window, &new_rule);
+            }
+        }
+
+        return Ok(());
+    }
+
+    let grid_id = grids[grid_idx].id;
+    let previous_target = {
+        let window = &mut grids[grid_idx]
+            .get_tile_by_id_mut(hwnd)
+            .expect("The tile got removed while updating its rule")
+            .window;
+        let removed_title_bar = window.should_remove_title_bar();
+        let previous_rule = std::mem::replace(&mut window.rule, rule);
+
+        if window.should_remove_title_bar() != removed_title_bar {
+            if removed_title_bar {
+                window.reset_style();
+            } else {
+                window.remove_title_bar();
+            }
+
+            window.update_style();
+        }
+
+        get_target_workspace(&previous_rule.unwrap_or_default())
+    };
+
+    match get_target_workspace(&new_rule) {
+        Some(id)
+            if id != grid_id && Some(id) != previous_target && grids.iter().any(|g| g.id == id) =>
+        {
+            debug!("Moving window {} to workspace {}", hwnd, id);
+
+            let tile = grids[grid_idx]
+                .close_tile_by_window_id(hwnd)
+                .expect("The tile got removed while updating its rule");
+
+            if !is_visible_workspace(id) {
+                tile.window.hide();
+            }
+
+            let grid = grids.iter_mut().find(|g| g.id == id).unwrap();
+
+            grid.split(tile.window);
+            grid.draw_grid();
+        }
+        _ => {}
+    }
+
+    grids[grid_idx].draw_grid();
+
+    Ok(())
+}
+
 pub fn handle(hwnd: HWND, force: bool) -> Result<(), Box<dyn std::error::Error>> {
     let title = util::get_title_of_window(hwnd);
     let min_width = CONFIG.lock().unwrap().min_width;
@@ -26,13 +225,12 @@ pub fn handle(hwnd: HWND, force: bool) -> Result<(), Box<dyn std::error::Error>>
         return Ok(());
     }
 
-    window.original_style = window.get_style().unwrap_or_default();
-    if window.original_style.contains(GwlStyle::MAXIMIZE) {
-        window.restore();
-        window.maximized = true;
-        window.original_style.remove(GwlStyle::MAXIMIZE);
+    if !is_managed(window.get_monitor()) {
+        debug!("Window is on a display that is not managed");
+        return Ok(());
     }
-    window.style = window.original_style;
+
+    window.original_style = window.get_style().unwrap_or_default();
     window.exstyle = window.get_ex_style().unwrap_or_default();
 
     let parent = window.get_parent_window();
@@ -41,55 +239,104 @@ pub fn handle(hwnd: HWND, force: bool) -> Result<(), Box<dyn std::error::Error>>
         || (window.original_style.contains(GwlStyle::CAPTION)
             && !window.exstyle.contains(GwlExStyle::DLGMODALFRAME));
 
-    let additional_rules = ADDITIONAL_RULES.lock().unwrap();
+    window.rule = find_rule(&window);
 
-    for rule in CONFIG
-        .lock()
-        .unwrap()
-        .rules
-        .iter()
-        .chain(additional_rules.iter())
-    {
-        // checks for path
-        let process_name = if rule.pattern.to_string().contains('\\') {
-            window.get_process_path()
-        } else {
-            window.get_process_name()
-        };
-
-        let window_name = window.title.clone();
-
-        if rule.pattern.is_match(&process_name) || rule.pattern.is_match(&window_name) {
-            debug!("Rule({:?}) matched!", rule.pattern);
-            window.rule = Some(rule.clone());
-            break;
+    name_change::remember(window.id, window.rule.clone(), force);
+
+    let rule = window.rule.clone().unwrap_or_default();
+    // dialogs, child windows etc. never get managed, so floating rules don't move them either
+    let is_manageable = parent.is_err() && correct_style;
+
+    // forcing a floating window to be managed happens through toggling the floating mode
+    if rule.floating && !force {
+        if is_manageable {
+            debug!("Window is floating");
+            return apply_floating_rule(&window, &rule);
         }
+
+        return Ok(());
     }
 
-    let rule = window.rule.clone().unwrap_or_default();
-    let should_manage = force || (rule.manage && parent.is_err() && correct_style);
+    let should_manage = force || (rule.manage && is_manageable);
 
     if should_manage {
         debug!("Managing window");
+
         let mut workspace_id = *WORKSPACE_ID.lock().unwrap();
 
-        if rule.workspace_id != -1 {
-            workspace_id = rule.workspace_id;
-            change_workspace(workspace_id, false)?;
+        if let Some(id) = get_target_workspace(&rule) {
+            workspace_id = id;
+
+            if rule.follow {
+                change_workspace(workspace_id, false)?;
+            }
+        }
+
+        let is_visible = is_visible_workspace(workspace_id);
+        let mut grids = GRIDS.lock().unwrap();
+        let grid = grids.iter_mut().find(|g| g.id == workspace_id).unwrap();
+
+        // the window has to stay untouched if it doesn't get managed
+        if !is_managed(grid.display.hmonitor) {
+            debug!(
+                "Workspace {} is on a display that is not managed",
+                workspace_id
+            );
+            return Ok(());
+        }
+
+        if window.original_style.contains(GwlStyle::MAXIMIZE) {
+            window.restore();
+            window.maximized = true;
+            window.original_style.remove(GwlStyle::MAXIMIZE);
         }
+        window.style = window.original_style;
 
-        if CONFIG.lock().unwrap().remove_title_bar {
+        if window.should_remove_title_bar() {
             window.remove_title_bar();
             window.update_style();
         }
 
-        let mut grids = GRIDS.lock().unwrap();
-        let grid = grids.iter_mut().find(|g| g.id == workspace_id).unwrap();
+        // the window stays hidden until its workspace becomes visible
+        if !is_visible {
+            window.hide();
+        }
 
         window.original_rect = window.get_rect()?;
 
+        hooks::emit("window_managed", vec![hooks::window_to_dynamic(&window)]);
+
+        if let Some(idx) = rule.on_manage {
+            // the callback gets called by the main loop, because GRIDS is locked here
+            if let Some(fp) = CALLBACKS.lock().unwrap().get(idx).cloned() {
+                CHANNEL
+                    .sender
+                    .clone()
+                    .send(Event::Callback(fp, vec![hooks::window_to_dynamic(&window)]))?;
+            }
+        }
+
+        let focused_tile = grid
+            .get_focused_tile()
+            .map(|tile| (tile.window.id, tile.split_direction));
+
+        if let Some(direction) = rule.split_direction {
+            grid.set_focused_split_direction(direction);
+        }
+
         grid.split(window);
 
+        // the split direction of the rule only applies to the insertion of this window
+        if let Some((id, direction)) = focused_tile {
+            if let Some(tile) = grid.get_tile_by_id_mut(id) {
+                tile.split_direction = direction;
+            }
+        }
+
+        if rule.fullscreen {
+            grid.fullscreen = true;
+        }
+
         grid.draw_grid();
     }
 
@@ -1,16 +1,115 @@
 use crate::{
-    bar, config::Config, display::get_display_by_hmonitor, keybindings, startup, task_bar,
+    bar,
+    config::{
+        self,
+        diagnostic::{Diagnostic, Severity},
+        diff::ConfigDiff,
+        field::UNAPPLIED_CONFIG,
+        Config,
+    },
+    display::{
+        get_configured_work_modes, get_display_by_hmonitor_or_fallback, is_managed, scale_by_dpi,
+    },
+    event_handler::{
+        keybinding::{toggle_monitor_work_mode, toggle_work_mode},
+        winevent::show,
+    },
+    keybindings,
+    popup::Popup,
+    startup, task_bar,
+    tile::Tile,
+    window::Window,
     with_current_grid, CONFIG, DISPLAYS, GRIDS, WORK_MODE,
 };
+use log::{error, info, warn};
 
-pub fn update_config(new_config: Config) -> Result<(), Box<dyn std::error::Error>> {
-    keybindings::unregister();
+/// Logs the diagnostics and shows them in a popup
+pub fn report_diagnostics(diagnostics: &[Diagnostic]) {
+    if diagnostics.is_empty() {
+        return;
+    }
+
+    for diagnostic in diagnostics {
+        match diagnostic.severity {
+            Severity::Error => error!("{}", diagnostic),
+            Severity::Warning => warn!("{}", diagnostic),
+        }
+    }
+
+    let mut text = vec![format!(
+        "Found {} problem(s) in the config:",
+        diagnostics.len()
+    )];
 
-    let config = CONFIG.lock().unwrap().clone();
+    text.extend(diagnostics.iter().map(|d| d.to_string()));
+
+    Popup::new()
+        .with_text(&text.iter().map(|t| t.as_str()).collect::<Vec<&str>>())
+        .with_padding(5)
+        .create();
+}
+
+/// Loads the config from its file again. Returns whether the config could be evaluated, otherwise
+/// the current config stays active.
+pub fn reload_config() -> Result<bool, Box<dyn std::error::Error>> {
+    match config::rhai::engine::parse_config() {
+        Ok((config, diagnostics)) => {
+            report_diagnostics(&diagnostics);
+            update_config(config)?;
+            config::rhai::hooks::emit("config_reloaded", Vec::new());
+            Ok(true)
+        }
+        Err(diagnostic) => {
+            report_diagnostics(&[diagnostic]);
+            Ok(false)
+        }
+    }
+}
+
+fn has_title_bar_override(tile: &Tile) -> bool {
+    tile.window
+        .rule
+        .as_ref()
+        .and_then(|rule| rule.remove_title_bar)
+        .is_some()
+}
+
+/// Matches the rules of every managed window again and applies the ones that changed
+fn update_rules() -> Result<(), Box<dyn std::error::Error>> {
+    let windows: Vec<Window> = GRIDS
+        .lock()
+        .unwrap()
+        .iter()
+        .flat_map(|g| g.tiles.iter().map(|t| t.window.clone()))
+        .collect();
+
+    for window in windows {
+        let rule = show::find_rule(&window);
+
+        if rule != window.rule {
+            show::update_rule(window.id, rule)?;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn update_config(new_config: Config) -> Result<(), Box<dyn std::error::Error>> {
+    // changes that scripts made at runtime are already part of the active config, so their
+    // effects have to be applied based on the config before them
+    let config = UNAPPLIED_CONFIG
+        .lock()
+        .unwrap()
+        .take()
+        .unwrap_or_else(|| CONFIG.lock().unwrap().clone());
+    let diff = ConfigDiff::new(&config, &new_config);
     let work_mode = *WORK_MODE.lock().unwrap();
     let mut draw_app_bar = false;
+    let mut redraw_app_bar = false;
     let mut update_grid_displays = false;
 
+    info!("Config diff: {}", diff);
+
     if work_mode {
         if config.remove_task_bar && !new_config.remove_task_bar {
             task_bar::show_taskbars();
@@ -25,15 +124,33 @@ pub fn update_config(new_config: Config) -> Result<(), Box<dyn std::error::Error
         }
 
         if config.display_app_bar && new_config.display_app_bar {
-            if config.bar != new_config.bar || config.light_theme != new_config.light_theme {
+            if diff.bar_changed
+                || diff.themes_changed
+                || diff.has_changed("active_theme")
+                || diff.has_changed("light_theme")
+                || diff.has_changed("dpi_scaling")
+            {
                 bar::close::close();
                 draw_app_bar = true;
+
+                for d in DISPLAYS.lock().unwrap().iter_mut() {
+                    d.bottom += scale_by_dpi(config.bar.height, d.dpi, config.dpi_scaling);
+                    d.bottom -= scale_by_dpi(new_config.bar.height, d.dpi, new_config.dpi_scaling);
+                }
+
+                update_grid_displays = true;
+            } else if diff.components_changed
+                || diff.workspace_settings_changed
+                || diff.has_changed("mode_meta")
+            {
+                // the components get rendered from the config, so a redraw is enough
+                redraw_app_bar = true;
             }
         } else if config.display_app_bar && !new_config.display_app_bar {
             bar::close::close();
 
             for d in DISPLAYS.lock().unwrap().iter_mut() {
-                d.bottom += config.bar.height;
+                d.bottom += scale_by_dpi(config.bar.height, d.dpi, config.dpi_scaling);
             }
 
             update_grid_displays = true;
@@ -41,7 +158,7 @@ pub fn update_config(new_config: Config) -> Result<(), Box<dyn std::error::Error
             draw_app_bar = true;
 
             for d in DISPLAYS.lock().unwrap().iter_mut() {
-                d.bottom -= config.bar.height;
+                d.bottom -= scale_by_dpi(new_config.bar.height, d.dpi, new_config.dpi_scaling);
             }
 
             update_grid_displays = true;
@@ -49,7 +166,7 @@ pub fn update_config(new_config: Config) -> Result<(), Box<dyn std::error::Error
 
         if update_grid_displays {
             for grid in GRIDS.lock().unwrap().iter_mut() {
-                grid.display = get_display_by_hmonitor(grid.display.hmonitor);
+                grid.display = get_display_by_hmonitor_or_fallback(grid.display.hmonitor);
             }
         }
     }
@@ -58,7 +175,8 @@ pub fn update_config(new_config: Config) -> Result<(), Box<dyn std::error::Error
         let mut grids = GRIDS.lock().unwrap();
 
         for grid in grids.iter_mut() {
-            for tile in &mut grid.tiles {
+            // windows whose rule decides about the title bar don't follow the setting
+            for tile in grid.tiles.iter_mut().filter(|t| !has_title_bar_override(t)) {
                 tile.window.reset_style();
                 tile.window.update_style();
             }
@@ -67,29 +185,73 @@ pub fn update_config(new_config: Config) -> Result<(), Box<dyn std::error::Error
         let mut grids = GRIDS.lock().unwrap();
 
         for grid in grids.iter_mut() {
-            for tile in &mut grid.tiles {
+            for tile in grid.tiles.iter_mut().filter(|t| !has_title_bar_override(t)) {
                 tile.window.remove_title_bar();
                 tile.window.update_style();
             }
         }
     }
 
-    if config.launch_on_startup != new_config.launch_on_startup {
+    if diff.has_changed("launch_on_startup") {
         startup::set_launch_on_startup(new_config.launch_on_startup)?;
     }
 
+    let work_mode_changed = diff.has_changed("work_mode") && new_config.work_mode != work_mode;
+
     *CONFIG.lock().unwrap() = new_config;
 
     if draw_app_bar {
         bar::create::create()?;
         bar::visibility::show();
+    } else if redraw_app_bar {
+        bar::redraw::redraw();
+    }
+
+    if diff.keybindings_changed() {
+        keybindings::reload()?;
+    }
+
+    if diff.rules_changed || diff.has_changed("merge_rules") {
+        update_rules()?;
+    }
+
+    if work_mode && (diff.monitor_settings_changed || diff.has_changed("multi_monitor")) {
+        let config = CONFIG.lock().unwrap().clone();
+
+        for (hmonitor, work_mode) in get_configured_work_modes(&config) {
+            if is_managed(hmonitor) != work_mode {
+                toggle_monitor_work_mode::set_work_mode(hmonitor, work_mode)?;
+            }
+        }
     }
 
-    keybindings::register()?;
+    let theme_changed = diff.themes_changed
+        || diff.bar_changed
+        || diff.has_changed("active_theme")
+        || diff.has_changed("light_theme");
 
-    with_current_grid(|grid| {
-        grid.draw_grid();
-    });
+    if theme_changed {
+        for grid in GRIDS.lock().unwrap().iter() {
+            grid.update_borders();
+        }
+    }
+
+    let layout_changed = update_grid_displays
+        || diff.has_changed("inner_gap")
+        || diff.has_changed("outer_gap")
+        || diff.has_changed("remove_title_bar")
+        || diff.has_changed("use_border")
+        || diff.has_changed("dpi_scaling");
+
+    if layout_changed {
+        with_current_grid(|grid| {
+            grid.draw_grid();
+        });
+    }
+
+    if work_mode_changed {
+        toggle_work_mode::handle()?;
+    }
 
     Ok(())
 }
@@ -1,28 +1,47 @@
-use crate::{event::Event, message_loop, util, CHANNEL, CONFIG, WORK_MODE};
+#[cfg(windows)]
+use crate::{config::rhai::hooks, event::Event, message_loop, util, CHANNEL, CONFIG, WORK_MODE};
+#[cfg(windows)]
+use condition::FocusedWindow;
+#[cfg(windows)]
 use key::Key;
+#[cfg(windows)]
 use keybinding::Keybinding;
+#[cfg(windows)]
 use keybinding_type::KeybindingType;
 use lazy_static::lazy_static;
+#[cfg(windows)]
 use log::{debug, error, info};
+#[cfg(windows)]
 use modifier::Modifier;
+#[cfg(windows)]
 use num_traits::FromPrimitive;
-use std::sync::{
-    atomic::{AtomicBool, Ordering},
-    Mutex,
+use std::sync::Mutex;
+#[cfg(windows)]
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashSet,
+    sync::atomic::{AtomicBool, Ordering},
 };
-use winapi::um::winuser::{RegisterHotKey, UnregisterHotKey, WM_HOTKEY};
+#[cfg(windows)]
+use winapi::um::winuser::{GetForegroundWindow, RegisterHotKey, UnregisterHotKey, WM_HOTKEY};
 
+pub mod condition;
 pub mod key;
 pub mod keybinding;
 pub mod keybinding_type;
 pub mod modifier;
 
+lazy_static! {
+    pub static ref MODE: Mutex<Option<String>> = Mutex::new(None);
+}
+
+#[cfg(windows)]
 lazy_static! {
     static ref UNREGISTER: AtomicBool = AtomicBool::new(false);
     static ref PREV_MODE: Mutex<Option<String>> = Mutex::new(None);
-    pub static ref MODE: Mutex<Option<String>> = Mutex::new(None);
 }
 
+#[cfg(windows)]
 fn unregister_keybindings<'a>(keybindings: impl Iterator<Item = &'a Keybinding>) {
     for kb in keybindings {
         info!("Unregistering {:?}", kb);
@@ -33,8 +52,49 @@ fn unregister_keybindings<'a>(keybindings: impl Iterator<Item = &'a Keybinding>)
     }
 }
 
+/// Whether every keybinding of the key combination has a condition
+#[cfg(windows)]
+fn is_conditional_only(keybindings: &[&Keybinding], kb: &Keybinding) -> bool {
+    keybindings
+        .iter()
+        .filter(|other| other.get_id() == kb.get_id())
+        .all(|other| other.condition.is_some())
+}
+
+/// Returns the keybindings that are registered while the mode is active
+#[cfg(windows)]
+fn active_keybindings<'a>(
+    keybindings: &'a [Keybinding],
+    mode: &Option<String>,
+) -> Vec<&'a Keybinding> {
+    keybindings
+        .iter()
+        .filter(|kb| match mode {
+            Some(mode) => {
+                kb.mode.as_ref() == Some(mode) || kb.typ == KeybindingType::ToggleMode(mode.clone())
+            }
+            None => kb.mode.is_none(),
+        })
+        .collect()
+}
+
+#[cfg(windows)]
 fn register_keybindings<'a>(keybindings: impl Iterator<Item = &'a Keybinding>) {
-    for kb in keybindings {
+    let keybindings = keybindings.collect::<Vec<&Keybinding>>();
+    // keybindings with a condition can share their key combination with other keybindings,
+    // but a hot key can only be registered once
+    let mut registered = HashSet::new();
+
+    for kb in &keybindings {
+        // these only get registered while one of the conditions matches the focused window
+        if is_conditional_only(&keybindings, kb) {
+            continue;
+        }
+
+        if !registered.insert(kb.get_id()) {
+            continue;
+        }
+
         info!("Registering {:?}", kb);
 
         unsafe {
@@ -49,13 +109,96 @@ fn register_keybindings<'a>(keybindings: impl Iterator<Item = &'a Keybinding>) {
     }
 }
 
+/// Registers the key combinations that only have keybindings with a condition, as long as one of
+/// the conditions matches the focused window. Otherwise the key combination would be taken from
+/// the focused window, even though none of the keybindings apply to it.
+#[cfg(windows)]
+fn update_conditional_keybindings(active: &[&Keybinding], registered: &mut HashSet<i32>) {
+    let candidates = active
+        .iter()
+        .filter(|kb| is_conditional_only(active, kb))
+        .collect::<Vec<_>>();
+
+    let window = if candidates.is_empty() {
+        None
+    } else {
+        FocusedWindow::get()
+    };
+
+    let wanted: HashSet<i32> = match window {
+        Some(window) => candidates
+            .iter()
+            .filter(|kb| {
+                kb.condition
+                    .as_ref()
+                    .map(|c| c.matches(&window))
+                    .unwrap_or(false)
+            })
+            .map(|kb| kb.get_id())
+            .collect(),
+        None => HashSet::new(),
+    };
+
+    for id in registered.difference(&wanted) {
+        unsafe {
+            UnregisterHotKey(std::ptr::null_mut(), *id);
+        }
+    }
+
+    registered.retain(|id| wanted.contains(id));
+
+    for kb in candidates {
+        if wanted.contains(&kb.get_id()) && registered.insert(kb.get_id()) {
+            debug!("Registering {:?} for the focused window", kb);
+
+            unsafe {
+                if RegisterHotKey(
+                    std::ptr::null_mut(),
+                    kb.get_id(),
+                    kb.modifier.bits(),
+                    kb.key as u32,
+                ) == 0
+                {
+                    error!("Failed to register {:?}", kb);
+                }
+            }
+        }
+    }
+}
+
+/// Finds the keybinding of the key combination.
+///
+/// Keybindings whose condition matches the focused window take precedence over the ones without
+/// a condition.
+#[cfg(windows)]
 fn get_keybinding(keybindings: &[Keybinding], key: Key, modifier: Modifier) -> Option<Keybinding> {
-    keybindings
+    let candidates = keybindings
         .iter()
-        .find(|kb| kb.key == key && kb.modifier == modifier)
+        .filter(|kb| kb.key == key && kb.modifier == modifier)
+        .collect::<Vec<&Keybinding>>();
+
+    if candidates.iter().any(|kb| kb.condition.is_some()) {
+        if let Some(window) = FocusedWindow::get() {
+            let matching = candidates.iter().find(|kb| {
+                kb.condition
+                    .as_ref()
+                    .map(|c| c.matches(&window))
+                    .unwrap_or(false)
+            });
+
+            if let Some(kb) = matching {
+                return Some((*kb).clone());
+            }
+        }
+    }
+
+    candidates
+        .into_iter()
+        .find(|kb| kb.condition.is_none())
         .cloned()
 }
 
+#[cfg(windows)]
 pub fn register() -> Result<(), Box<dyn std::error::Error>> {
     std::thread::spawn(|| {
         let keybindings = CONFIG.lock().unwrap().keybindings.clone();
@@ -68,15 +211,33 @@ pub fn register() -> Result<(), Box<dyn std::error::Error>> {
 
         register_keybindings(keybindings.iter().filter(|kb| kb.mode == None));
 
+        // the conditional keybindings that are currently registered
+        let conditional = RefCell::new(HashSet::new());
+        // the focused window the conditional keybindings got registered for
+        let foreground = Cell::new(None);
+
         message_loop::start(|maybe_msg| {
+            let mut prev_mode = PREV_MODE.lock().unwrap();
+
             if UNREGISTER.load(Ordering::SeqCst) {
                 debug!("Unregistering hot key manager");
-                unregister_keybindings(keybindings.iter().filter(|kb| kb.mode == None));
+                // only unregister the keybindings that are currently registered
+                if let Some(mode) = prev_mode.clone() {
+                    unregister_keybindings(keybindings.iter().filter(|kb| {
+                        kb.mode == Some(mode.clone())
+                            || kb.typ == KeybindingType::ToggleMode(mode.clone())
+                    }));
+                } else {
+                    unregister_keybindings(keybindings.iter().filter(|kb| kb.mode == None));
+                }
+                // without any keybindings every conditional keybinding gets unregistered
+                update_conditional_keybindings(&[], &mut conditional.borrow_mut());
+                // the next hot key manager registers the keybindings of the active mode again
+                *prev_mode = None;
                 UNREGISTER.store(false, Ordering::SeqCst);
                 return false;
             }
 
-            let mut prev_mode = PREV_MODE.lock().unwrap();
             let mode = MODE.lock().unwrap().clone();
 
             if *prev_mode != mode {
@@ -102,7 +263,19 @@ pub fn register() -> Result<(), Box<dyn std::error::Error>> {
                     );
                 }
 
+                update_conditional_keybindings(&[], &mut conditional.borrow_mut());
                 *prev_mode = mode;
+                foreground.set(None);
+            }
+
+            let hwnd = unsafe { GetForegroundWindow() } as isize;
+
+            if foreground.get() != Some(hwnd) {
+                foreground.set(Some(hwnd));
+                update_conditional_keybindings(
+                    &active_keybindings(&keybindings, &prev_mode),
+                    &mut conditional.borrow_mut(),
+                );
             }
 
             if let Some(msg) = maybe_msg {
@@ -113,10 +286,12 @@ pub fn register() -> Result<(), Box<dyn std::error::Error>> {
                 let work_mode = *WORK_MODE.lock().unwrap();
                 let modifier = Modifier::from_bits((msg.lParam & 0xffff) as u32).unwrap();
 
-                if let Some(key) = Key::from_isize(msg.lParam >> 16) {
-                    let kb = get_keybinding(&keybindings, key, modifier)
-                        .expect("Couldn't find keybinding");
+                let kb = Key::from_isize(msg.lParam >> 16)
+                    .and_then(|key| get_keybinding(&keybindings, key, modifier));
 
+                // there is no keybinding if the focus changed since the conditional keybindings
+                // got registered
+                if let Some(kb) = kb {
                     if work_mode || kb.typ == KeybindingType::ToggleWorkMode {
                         CHANNEL
                             .sender
@@ -134,11 +309,38 @@ pub fn register() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+#[cfg(windows)]
 pub fn unregister() {
     disable_mode();
     UNREGISTER.store(true, Ordering::SeqCst);
 }
 
+/// Registers the keybindings of the current config again.
+///
+/// Unlike `unregister` this keeps the active mode, as long as it still exists in the config.
+#[cfg(windows)]
+pub fn reload() -> Result<(), Box<dyn std::error::Error>> {
+    let mode = MODE.lock().unwrap().clone();
+
+    if let Some(mode) = mode {
+        let mode_exists = CONFIG
+            .lock()
+            .unwrap()
+            .keybindings
+            .iter()
+            .any(|kb| kb.mode == Some(mode.clone()));
+
+        if !mode_exists {
+            disable_mode();
+        }
+    }
+
+    UNREGISTER.store(true, Ordering::SeqCst);
+
+    register()
+}
+
+#[cfg(windows)]
 pub fn enable_mode(mode: &str) -> bool {
     let mut mode_guard = MODE.lock().unwrap();
     let mode = Some(mode.to_string());
@@ -149,6 +351,11 @@ pub fn enable_mode(mode: &str) -> bool {
 
     *mode_guard = mode;
 
+    hooks::emit(
+        "mode_changed",
+        vec![mode_guard.clone().unwrap_or_default().into()],
+    );
+
     let sender = CHANNEL.sender.clone();
 
     let _ = sender
@@ -158,8 +365,11 @@ pub fn enable_mode(mode: &str) -> bool {
     true
 }
 
+#[cfg(windows)]
 pub fn disable_mode() {
-    *MODE.lock().unwrap() = None;
+    if MODE.lock().unwrap().take().is_some() {
+        hooks::emit("mode_changed", vec![().into()]);
+    }
 
     let sender = CHANNEL.sender.clone();
 
@@ -0,0 +1,132 @@
+use super::keybinding_type::KeybindingType;
+#[cfg(windows)]
+use crate::{event_handler::winevent::show, util, window::Window, GRIDS};
+use regex::Regex;
+use serde::{Serialize, Serializer};
+use std::fmt::Display;
+
+/// Serializes the regex by its pattern
+fn serialize_pattern<S: Serializer>(
+    regex: &Option<Regex>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match regex {
+        Some(regex) => serializer.serialize_str(regex.as_str()),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// Restricts a keybinding to specific windows. Every pattern and flag that is set has to match
+/// the focused window.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Condition {
+    #[serde(serialize_with = "serialize_pattern")]
+    pub process: Option<Regex>,
+    #[serde(serialize_with = "serialize_pattern")]
+    pub title: Option<Regex>,
+    #[serde(serialize_with = "serialize_pattern")]
+    pub class: Option<Regex>,
+    /// Whether the focused window has to be managed
+    pub managed: Option<bool>,
+    /// Whether the rule of the focused window has to make it float
+    pub floating: Option<bool>,
+}
+
+impl PartialEq for Condition {
+    fn eq(&self, other: &Self) -> bool {
+        let as_str = |regex: &Option<Regex>| regex.as_ref().map(|r| r.as_str().to_string());
+
+        as_str(&self.process) == as_str(&other.process)
+            && as_str(&self.title) == as_str(&other.title)
+            && as_str(&self.class) == as_str(&other.class)
+            && self.managed == other.managed
+            && self.floating == other.floating
+    }
+}
+
+impl Display for Condition {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let parts = [
+            ("process", &self.process),
+            ("title", &self.title),
+            ("class", &self.class),
+        ];
+
+        let flags = [("managed", self.managed), ("floating", self.floating)];
+
+        let parts = parts
+            .iter()
+            .filter_map(|(name, regex)| regex.as_ref().map(|r| format!("{}: {}", name, r)))
+            .chain(
+                flags
+                    .iter()
+                    .filter_map(|(name, flag)| flag.map(|f| format!("{}: {}", name, f))),
+            )
+            .collect::<Vec<String>>();
+
+        f.write_str(&parts.join(", "))
+    }
+}
+
+#[cfg(windows)]
+impl Condition {
+    pub fn matches(&self, window: &FocusedWindow) -> bool {
+        let is_match = |regex: &Option<Regex>, value: &str| {
+            regex.as_ref().map(|r| r.is_match(value)).unwrap_or(true)
+        };
+
+        let is_set = |flag: Option<bool>, value: bool| flag.map(|f| f == value).unwrap_or(true);
+
+        is_match(&self.process, &window.process_name)
+            && is_match(&self.title, &window.title)
+            && is_match(&self.class, &window.class_name)
+            && is_set(self.managed, window.managed)
+            && is_set(self.floating, window.floating)
+    }
+}
+
+/// The properties of the focused window that a condition can check
+#[cfg(windows)]
+pub struct FocusedWindow {
+    pub process_name: String,
+    pub title: String,
+    pub class_name: String,
+    /// Whether the window is a tile of one of the workspaces
+    pub managed: bool,
+    /// Whether the rule of the window makes it float
+    pub floating: bool,
+}
+
+#[cfg(windows)]
+impl FocusedWindow {
+    pub fn get() -> Option<Self> {
+        let hwnd = Window::get_foreground_window().ok()?;
+        let window = Window {
+            id: hwnd as i32,
+            title: util::get_title_of_window(hwnd).unwrap_or_default(),
+            ..Window::default()
+        };
+        let managed = GRIDS
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|g| g.get_tile_by_id(window.id).is_some());
+
+        Some(Self {
+            process_name: window.get_process_name(),
+            class_name: util::get_class_name_of_window(hwnd).unwrap_or_default(),
+            managed,
+            floating: show::find_rule(&window)
+                .map(|rule| rule.floating)
+                .unwrap_or(false),
+            title: window.title,
+        })
+    }
+}
+
+/// An action that only gets executed if the condition matches, created by the `when` operator
+#[derive(Debug, Clone)]
+pub struct ConditionalAction {
+    pub typ: KeybindingType,
+    pub condition: Condition,
+}
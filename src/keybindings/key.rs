@@ -1,5 +1,4 @@
 use strum_macros::EnumString;
-use winapi::um::winuser::{VK_LCONTROL, VK_LMENU, VK_LSHIFT};
 
 #[derive(Clone, Copy, FromPrimitive, ToPrimitive, PartialEq, EnumString, Display, Debug)]
 #[allow(dead_code)]
@@ -34,11 +33,11 @@ pub enum Key {
     Y = 0x59,
     Z = 0x5A,
     #[strum(serialize = "Shift")]
-    LShift = VK_LSHIFT as isize,
+    LShift = 0xA0,
     #[strum(serialize = "Control")]
-    LControl = VK_LCONTROL as isize,
+    LControl = 0xA2,
     #[strum(serialize = "Alt")]
-    LAlt = VK_LMENU as isize,
+    LAlt = 0xA4,
     Left = 0x25,
     Up = 0x26,
     Right = 0x27,
@@ -1,18 +1,49 @@
-use super::{key::Key, keybinding_type::KeybindingType, modifier::Modifier};
+use super::{condition::Condition, key::Key, keybinding_type::KeybindingType, modifier::Modifier};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
 use std::{fmt::Debug, str::FromStr};
 
-#[derive(Clone)]
+#[derive(Clone, PartialEq)]
 pub struct Keybinding {
     pub typ: KeybindingType,
     pub mode: Option<String>,
     pub key: Key,
     pub modifier: Modifier,
+    /// The keybinding only applies to the windows that match the condition
+    pub condition: Option<Condition>,
 }
 
 impl Keybinding {
     pub fn get_id(&self) -> i32 {
         (self.key as u32 + self.modifier.bits() * 1000) as i32
     }
+
+    /// Returns the key combination in the same format as it is written in the config (e.g. `Alt+H`)
+    pub fn get_key_combo(&self) -> String {
+        let modifiers = [
+            (Modifier::ALT, "Alt"),
+            (Modifier::CONTROL, "Control"),
+            (Modifier::SHIFT, "Shift"),
+        ];
+
+        modifiers
+            .iter()
+            .filter(|(m, _)| self.modifier.contains(*m))
+            .map(|(_, name)| name.to_string())
+            .chain(std::iter::once(self.key.to_string()))
+            .collect::<Vec<String>>()
+            .join("+")
+    }
+}
+
+impl Serialize for Keybinding {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("Keybinding", 4)?;
+        state.serialize_field("key", &self.get_key_combo())?;
+        state.serialize_field("mode", &self.mode)?;
+        state.serialize_field("typ", &self.typ)?;
+        state.serialize_field("when", &self.condition)?;
+        state.end()
+    }
 }
 
 impl FromStr for Keybinding {
@@ -21,20 +52,16 @@ impl FromStr for Keybinding {
         let key_combo_parts = s.split('+').collect::<Vec<&str>>();
         let modifier_count = key_combo_parts.len() - 1;
 
-        let modifier = key_combo_parts
-            .iter()
-            .take(modifier_count)
-            .map(|x| match *x {
+        let mut modifier = Modifier::default();
+
+        for x in key_combo_parts.iter().take(modifier_count) {
+            modifier.insert(match *x {
                 "Alt" => Modifier::ALT,
                 "Control" => Modifier::CONTROL,
                 "Shift" => Modifier::SHIFT,
-                _ => Modifier::default(),
-            })
-            .fold(Modifier::default(), |mut sum, crr| {
-                sum.insert(crr);
-
-                sum
+                _ => return Err(format!("Invalid modifier {}", x).into()),
             });
+        }
 
         let raw_key = key_combo_parts.iter().last().unwrap();
         let key = Key::from_str(raw_key)
@@ -46,6 +73,7 @@ impl FromStr for Keybinding {
             mode: None,
             modifier,
             key,
+            condition: None,
         })
     }
 }
@@ -53,20 +81,27 @@ impl FromStr for Keybinding {
 impl Debug for Keybinding {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let modifier_str = format!("{:?}", self.modifier).replace(" | ", "+");
+        let condition_str = self
+            .condition
+            .as_ref()
+            .map(|c| format!(" when {}", c))
+            .unwrap_or_default();
         if modifier_str == "(empty)" {
             f.write_str(&format!(
-                "Keybinding({:?}, {}, {})",
+                "Keybinding({:?}, {}, {}){}",
                 self.key,
                 self.typ,
-                self.get_id()
+                self.get_id(),
+                condition_str
             ))
         } else {
             f.write_str(&format!(
-                "Keybinding({}+{:?}, {}, {})",
+                "Keybinding({}+{:?}, {}, {}){}",
                 modifier_str,
                 self.key,
                 self.typ,
-                self.get_id()
+                self.get_id(),
+                condition_str
             ))
         }
     }
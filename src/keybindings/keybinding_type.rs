@@ -1,7 +1,18 @@
-use crate::{direction::Direction, split_direction::SplitDirection};
+use crate::{
+    config::rhai::engine::CALLBACKS, direction::Direction, split_direction::SplitDirection,
+};
+use serde::{Serialize, Serializer};
+
+/// Serializes the callback by the name of its function
+pub fn serialize_callback<S: Serializer>(idx: &usize, serializer: S) -> Result<S::Ok, S::Error> {
+    match CALLBACKS.lock().unwrap().get(*idx) {
+        Some(fp) => serializer.serialize_str(fp.fn_name()),
+        None => serializer.serialize_none(),
+    }
+}
 
 pub type Command = String;
-#[derive(Display, Clone, PartialEq, Debug)]
+#[derive(Display, Clone, PartialEq, Debug, Serialize)]
 pub enum KeybindingType {
     CloseTile,
     IgnoreTile,
@@ -13,16 +24,23 @@ pub enum KeybindingType {
     ToggleFloatingMode,
     ToggleMode(String),
     ToggleWorkMode,
+    ToggleMonitorWorkMode,
+    SwitchProfile(String),
+    SwitchTheme(String),
     IncrementConfig(String, i32),
     DecrementConfig(String, i32),
     ToggleConfig(String),
     MoveWorkspaceToMonitor(i32),
     ToggleFullscreen,
+    /// Shows which rules apply to the focused window
+    ExplainWindow,
     Launch(Command),
     Focus(Direction),
     Resize(Direction, i32),
     Swap(Direction),
-    Callback(usize),
+    Callback(#[serde(serialize_with = "serialize_callback")] usize),
     MoveToWorkspace(i32),
     Split(SplitDirection),
+    /// Executes the actions one after another
+    Sequence(Vec<KeybindingType>),
 }
@@ -13,9 +13,8 @@ pub fn setup() -> Result<(), Box<dyn std::error::Error>> {
 
     #[cfg(not(debug_assertions))]
     {
-        path = dirs::config_dir().expect("Failed to get config directory");
+        path = crate::config::location::get_config_dir();
 
-        path.push("nog");
         path.push("log");
     }
 
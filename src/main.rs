@@ -5,67 +5,109 @@ extern crate num_derive;
 #[macro_use]
 extern crate strum_macros;
 
-use config::{rule::Rule, Config};
+#[cfg(windows)]
+use config::rule::Rule;
+use config::Config;
+#[cfg(windows)]
 use crossbeam_channel::select;
 use display::Display;
+#[cfg(windows)]
 use event::Event;
 use event::EventChannel;
-use hot_reload::update_config;
 use lazy_static::lazy_static;
-use log::{error, info};
+#[cfg(windows)]
+use log::info;
+use log::{error, warn};
 use std::collections::HashMap;
 use std::sync::Mutex;
+#[cfg(windows)]
 use tile_grid::TileGrid;
+#[cfg(windows)]
 use winapi::shared::windef::HWND;
+#[cfg(windows)]
 use workspace::{change_workspace, Workspace};
 
+#[cfg(windows)]
 mod bar;
+/// Only the components are needed to evaluate the config on other platforms
+#[cfg(not(windows))]
+mod bar {
+    pub mod component;
+}
+mod check_config;
 mod config;
 mod direction;
 mod display;
+mod dump_config;
 mod event;
+#[cfg(windows)]
 mod event_handler;
+#[cfg(windows)]
 mod hot_reload;
 mod keybindings;
+#[cfg(windows)]
 mod logging;
+#[cfg(windows)]
 mod message_loop;
+#[cfg(windows)]
 mod popup;
 mod split_direction;
+#[cfg(windows)]
 mod startup;
+#[cfg(windows)]
 mod task_bar;
+#[cfg(windows)]
 mod tile;
+#[cfg(windows)]
 mod tile_grid;
+#[cfg(windows)]
 mod tray;
+#[cfg(windows)]
 mod update;
 mod util;
+#[cfg(windows)]
 mod win_event_handler;
+#[cfg(windows)]
 mod window;
+#[cfg(windows)]
 mod workspace;
 
 lazy_static! {
     pub static ref WORK_MODE: Mutex<bool> = Mutex::new(CONFIG.lock().unwrap().work_mode);
     pub static ref CONFIG: Mutex<Config> = Mutex::new(
         config::rhai::engine::parse_config()
+            .map(|(config, diagnostics)| {
+                for diagnostic in diagnostics {
+                    warn!("{}", diagnostic);
+                }
+                config
+            })
             .map_err(|e| error!("{}", e))
             .expect("Failed to load config")
     );
     pub static ref DISPLAYS: Mutex<Vec<Display>> = Mutex::new(Vec::new());
     pub static ref CHANNEL: EventChannel = EventChannel::default();
+    pub static ref VISIBLE_WORKSPACES: Mutex<HashMap<i32, i32>> = Mutex::new(HashMap::new());
+    pub static ref WORKSPACE_ID: Mutex<i32> = Mutex::new(1);
+}
+
+#[cfg(windows)]
+lazy_static! {
     pub static ref ADDITIONAL_RULES: Mutex<Vec<Rule>> = Mutex::new(Vec::new());
     pub static ref GRIDS: Mutex<Vec<TileGrid>> =
         Mutex::new((1..11).map(TileGrid::new).collect::<Vec<TileGrid>>());
     pub static ref WORKSPACES: Mutex<Vec<Workspace>> =
         Mutex::new((1..11).map(Workspace::new).collect::<Vec<Workspace>>());
-    pub static ref VISIBLE_WORKSPACES: Mutex<HashMap<i32, i32>> = Mutex::new(HashMap::new());
-    pub static ref WORKSPACE_ID: Mutex<i32> = Mutex::new(1);
 }
 
+#[cfg(windows)]
 fn unmanage_everything() -> Result<(), util::WinApiResultError> {
     let mut grids = GRIDS.lock().unwrap();
 
     for grid in grids.iter_mut() {
         for tile in &mut grid.tiles.clone() {
             grid.close_tile_by_window_id(tile.window.id);
+            event_handler::winevent::name_change::forget(tile.window.id);
             tile.window.reset();
         }
     }
@@ -73,6 +115,7 @@ fn unmanage_everything() -> Result<(), util::WinApiResultError> {
     Ok(())
 }
 
+#[cfg(windows)]
 pub fn with_current_grid<TFunction, TReturn>(f: TFunction) -> TReturn
 where
     TFunction: Fn(&mut TileGrid) -> TReturn,
@@ -80,6 +123,7 @@ where
     with_grid_by_id(*WORKSPACE_ID.lock().unwrap(), f)
 }
 
+#[cfg(windows)]
 pub fn with_grid_by_id<TFunction, TReturn>(id: i32, f: TFunction) -> TReturn
 where
     TFunction: Fn(&mut TileGrid) -> TReturn,
@@ -90,6 +134,7 @@ where
     f(&mut grid)
 }
 
+#[cfg(windows)]
 fn on_quit() -> Result<(), util::WinApiResultError> {
     unmanage_everything()?;
 
@@ -108,6 +153,7 @@ fn on_quit() -> Result<(), util::WinApiResultError> {
     std::process::exit(0);
 }
 
+#[cfg(windows)]
 fn run() -> Result<(), Box<dyn std::error::Error>> {
     let receiver = CHANNEL.receiver.clone();
 
@@ -165,17 +211,27 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
                 let msg = maybe_msg.unwrap();
                 let _ = match msg {
                     Event::Keybinding(kb) => event_handler::keybinding::handle(kb),
+                    Event::Action(typ) => event_handler::keybinding::execute(typ),
                     Event::RedrawAppBar => Ok(bar::redraw::redraw()),
+                    Event::DisplayChange => event_handler::display_change::handle(),
+                    Event::Hook(event, args) => Ok(config::rhai::hooks::dispatch(&event, args)),
+                    Event::Timer(id) => Ok(config::rhai::timers::fire(id)),
+                    Event::Callback(fp, args) => Ok(config::rhai::engine::call_fn_ptr(fp, args)),
                     Event::WinEvent(ev) => event_handler::winevent::handle(ev),
                     Event::Exit => {
                         tray::remove_icon(*tray::WINDOW.lock().unwrap() as HWND);
                         on_quit()?;
                         break;
                     },
+                    Event::ConfigChanged => {
+                        let config = CONFIG.lock().unwrap().clone();
+
+                        hot_reload::update_config(config)
+                    }
                     Event::ReloadConfig => {
                         info!("Reloading Config");
 
-                        update_config(config::rhai::engine::parse_config().expect("Failed to load config"))
+                        hot_reload::reload_config().map(|_| ())
                     }
                 }.map_err(|e| {
                     error!("{}", e);
@@ -188,6 +244,29 @@ fn run() -> Result<(), Box<dyn std::error::Error>> {
 }
 
 fn main() {
+    let args = config::location::init(std::env::args().skip(1).collect());
+
+    if args.get(0).map(|a| a.as_str()) == Some("check-config") {
+        std::process::exit(check_config::run(args.get(1)));
+    }
+
+    if args.iter().any(|a| a == "--dump-config") {
+        std::process::exit(dump_config::run());
+    }
+
+    #[cfg(windows)]
+    start();
+
+    #[cfg(not(windows))]
+    {
+        eprintln!("nog only runs on windows. Use check-config or --dump-config to check a config");
+        std::process::exit(1);
+    }
+}
+
+/// Starts the window manager
+#[cfg(windows)]
+fn start() {
     logging::setup().expect("Failed to setup logging");
 
     let panic = std::panic::catch_unwind(|| {
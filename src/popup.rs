@@ -5,15 +5,18 @@ use std::{
     sync::{Arc, Mutex},
     thread,
 };
+use winapi::shared::windef::HDC;
 use winapi::shared::windef::HWND;
 use winapi::shared::windef::RECT;
 use winapi::um::wingdi::CreateSolidBrush;
+use winapi::um::wingdi::DeleteObject;
 use winapi::um::wingdi::SetBkColor;
 use winapi::um::wingdi::SetTextColor;
 
 use winapi::um::winuser::{
-    BeginPaint, DefWindowProcA, EndPaint, GetDC, LoadCursorA, RegisterClassA, ReleaseDC, SetCursor,
-    ShowWindow, IDC_ARROW, PAINTSTRUCT, SW_SHOW, WM_PAINT, WM_SETCURSOR,
+    BeginPaint, DefWindowProcA, EndPaint, FillRect, GetClientRect, GetDC, LoadCursorA,
+    RegisterClassA, ReleaseDC, SetCursor, ShowWindow, IDC_ARROW, PAINTSTRUCT, SW_SHOW,
+    WM_ERASEBKGND, WM_PAINT, WM_SETCURSOR,
 };
 use winapi::{
     shared::minwindef::{HINSTANCE, LPARAM, LRESULT, UINT, WPARAM},
@@ -39,6 +42,8 @@ pub struct Popup {
     width: i32,
     padding: i32,
     height: i32,
+    /// The dpi of the display the popup is shown on
+    dpi: u32,
     text: Vec<String>,
     pub actions: Vec<PopupAction>,
 }
@@ -49,6 +54,7 @@ impl Popup {
             window: Window::default(),
             width: 0,
             height: 0,
+            dpi: 0,
             padding: 5,
             text: Vec::new(),
             actions: Vec::new(),
@@ -81,6 +87,9 @@ impl Popup {
                 let name = CString::new("NogPopup").unwrap();
                 let display = get_primary_display();
 
+                popup.dpi = display.dpi;
+                popup.padding = display.scale(popup.padding);
+
                 let window_handle = winapi::um::winuser::CreateWindowExA(
                     winapi::um::winuser::WS_EX_NOACTIVATE | winapi::um::winuser::WS_EX_TOPMOST,
                     name.as_ptr(),
@@ -100,7 +109,7 @@ impl Popup {
                 let hdc = GetDC(window_handle);
                 let c_text = util::to_widestring(&popup.text.join("\n"));
 
-                bar::font::set_font(hdc);
+                bar::font::set_font(hdc, popup.dpi);
                 DrawTextW(hdc, c_text.as_ptr(), -1, &mut rect, DT_CALCRECT);
 
                 let width = rect.right - rect.left;
@@ -138,17 +147,15 @@ impl Popup {
 }
 
 pub fn init() {
-    bar::font::load_font();
     unsafe {
         let instance = winapi::um::libloaderapi::GetModuleHandleA(std::ptr::null_mut());
-        let brush = CreateSolidBrush(CONFIG.lock().unwrap().bar.color as u32);
         let name = CString::new("NogPopup").unwrap();
 
+        // the background gets painted on WM_ERASEBKGND, so it follows the active theme
         let class = WNDCLASSA {
             hInstance: instance as HINSTANCE,
             lpszClassName: name.as_ptr(),
             lpfnWndProc: Some(window_cb),
-            hbrBackground: brush,
             ..WNDCLASSA::default()
         };
 
@@ -182,6 +189,16 @@ unsafe extern "system" fn window_cb(
         *POPUP.lock().unwrap() = None;
     } else if msg == WM_SETCURSOR {
         SetCursor(LoadCursorA(std::ptr::null_mut(), IDC_ARROW as *const i8));
+    } else if msg == WM_ERASEBKGND {
+        let brush = CreateSolidBrush(CONFIG.lock().unwrap().get_theme().background as u32);
+        let mut rect = RECT::default();
+
+        GetClientRect(hwnd, &mut rect);
+        FillRect(w_param as HDC, &rect, brush);
+
+        DeleteObject(brush as *mut std::ffi::c_void);
+
+        return 1;
     } else if msg == WM_PAINT {
         let popup = POPUP.lock().unwrap().clone().unwrap();
         let mut rect = RECT::default();
@@ -198,9 +215,10 @@ unsafe extern "system" fn window_cb(
         BeginPaint(hwnd, &mut paint);
 
         let hdc = GetDC(hwnd);
-        bar::font::set_font(hdc);
-        SetTextColor(hdc, 0x00ffffff);
-        SetBkColor(hdc, CONFIG.lock().unwrap().bar.color as u32);
+        bar::font::set_font(hdc, popup.dpi);
+        let theme = CONFIG.lock().unwrap().get_theme();
+        SetTextColor(hdc, theme.foreground as u32);
+        SetBkColor(hdc, theme.background as u32);
 
         let c_text = util::to_widestring(&popup.text.join("\n"));
         DrawTextW(hdc, c_text.as_ptr(), -1, &mut rect, 0);
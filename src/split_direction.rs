@@ -1,4 +1,6 @@
-#[derive(Clone, EnumString, Copy, Debug, PartialEq)]
+use serde::Serialize;
+
+#[derive(Clone, EnumString, Copy, Debug, PartialEq, Serialize)]
 pub enum SplitDirection {
     Horizontal,
     Vertical,
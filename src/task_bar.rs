@@ -36,7 +36,7 @@ pub struct TaskBar {
 }
 
 pub fn show_taskbars() {
-    foreach_taskbar(|hwnd| {
+    foreach_taskbar(false, |hwnd| {
         info!("Showing taskbar {}", hwnd);
         unsafe {
             ShowWindow(hwnd as HWND, SW_SHOW);
@@ -45,8 +45,9 @@ pub fn show_taskbars() {
 
     update_task_bars();
 }
+/// Hides the taskbars of every display that is managed by nog
 pub fn hide_taskbars() {
-    foreach_taskbar(|hwnd| {
+    foreach_taskbar(true, |hwnd| {
         info!("Hiding taskbar {}", hwnd);
         unsafe {
             ShowWindow(hwnd as HWND, SW_HIDE);
@@ -55,11 +56,38 @@ pub fn hide_taskbars() {
 
     update_task_bars();
 }
-fn foreach_taskbar(cb: fn(i32) -> ()) {
+/// Shows or hides the taskbar of a single display
+pub fn set_taskbar_visibility(hmonitor: i32, visible: bool) {
+    let maybe_task_bar = DISPLAYS
+        .lock()
+        .unwrap()
+        .iter()
+        .find(|d| d.hmonitor == hmonitor)
+        .and_then(|d| d.task_bar);
+
+    if let Some(task_bar) = maybe_task_bar {
+        info!(
+            "{} taskbar {}",
+            if visible { "Showing" } else { "Hiding" },
+            task_bar.hwnd
+        );
+        unsafe {
+            ShowWindow(
+                task_bar.hwnd as HWND,
+                if visible { SW_SHOW } else { SW_HIDE },
+            );
+        }
+    }
+
+    update_task_bars();
+}
+fn foreach_taskbar(only_managed: bool, cb: fn(i32) -> ()) {
     let mut displays = DISPLAYS.lock().unwrap();
     displays.sort_by(|x, y| y.is_primary.cmp(&x.is_primary));
 
-    let displays = displays.iter().filter(|x| x.task_bar.is_some());
+    let displays = displays
+        .iter()
+        .filter(|x| x.task_bar.is_some() && (!only_managed || x.work_mode));
 
     for display in displays {
         cb(display.task_bar.unwrap().hwnd);
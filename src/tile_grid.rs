@@ -14,6 +14,9 @@ use winapi::um::winuser::SWP_NOSENDCHANGING;
 #[derive(Clone)]
 pub struct TileGrid {
     pub display: Display,
+    /// Name of the display this grid had to leave, because the display got disconnected.
+    /// The grid moves back once the display is available again.
+    pub displaced_from: Option<String>,
     pub id: i32,
     pub fullscreen: bool,
     pub focus_stack: Vec<(Direction, i32)>,
@@ -35,6 +38,7 @@ impl TileGrid {
         Self {
             id,
             display: get_primary_display(),
+            displaced_from: None,
             fullscreen: false,
             tiles: Vec::new(),
             focus_stack: Vec::with_capacity(5),
@@ -476,6 +480,7 @@ impl TileGrid {
 
             (config.inner_gap, config.outer_gap)
         };
+        let (padding, margin) = (self.display.scale(padding), self.display.scale(margin));
         let display_height = self.display.working_area_height() - margin * 2 - padding * 2;
         let display_width = self.display.working_area_width() - margin * 2 - padding * 2;
         let column_width = display_width / self.columns;
@@ -672,9 +677,27 @@ impl TileGrid {
         println!();
     }
 
+    /// Colors the border of the focused tile with the focused_border color of the theme and
+    /// the borders of the other tiles with the border color. Tiles whose color isn't set by the
+    /// theme get the default border of windows.
+    pub fn update_borders(&self) {
+        let theme = CONFIG.lock().unwrap().get_theme();
+
+        for tile in &self.tiles {
+            tile.window
+                .set_border_color(if Some(tile.window.id) == self.focused_window_id {
+                    theme.focused_border
+                } else {
+                    theme.border
+                });
+        }
+    }
+
     pub fn draw_grid(&self) {
         debug!("Drawing grid");
 
+        self.update_borders();
+
         if self.fullscreen {
             self.draw_tile(self.get_focused_tile().expect("Couldn't get focused tile"));
             return;
@@ -1,8 +1,11 @@
+use crate::dump_config;
 use crate::event::Event;
+use crate::popup::Popup;
 use crate::util;
 use crate::CHANNEL;
 use crate::{message_loop, CONFIG};
 use lazy_static::lazy_static;
+use log::error;
 use num_traits::FromPrimitive;
 use std::sync::Mutex;
 use winapi::shared::minwindef::HINSTANCE;
@@ -45,6 +48,7 @@ use winapi::um::winuser::WM_APP;
 use winapi::um::winuser::WM_CLOSE;
 use winapi::um::winuser::WM_COMMAND;
 use winapi::um::winuser::WM_CREATE;
+use winapi::um::winuser::WM_DISPLAYCHANGE;
 use winapi::um::winuser::WM_INITMENUPOPUP;
 use winapi::um::winuser::WM_RBUTTONUP;
 use winapi::um::winuser::WNDCLASSA;
@@ -57,6 +61,7 @@ lazy_static! {
 enum PopupId {
     Exit = 1000,
     Reload = 1001,
+    DumpConfig = 1002,
 }
 
 unsafe extern "system" fn window_cb(
@@ -67,6 +72,12 @@ unsafe extern "system" fn window_cb(
 ) -> LRESULT {
     if msg == WM_CREATE {
         add_icon(hwnd);
+    } else if msg == WM_DISPLAYCHANGE {
+        CHANNEL
+            .sender
+            .clone()
+            .send(Event::DisplayChange)
+            .expect("Failed to send display-change event");
     } else if msg == WM_CLOSE {
         CHANNEL
             .sender
@@ -86,6 +97,15 @@ unsafe extern "system" fn window_cb(
                         .send(Event::ReloadConfig)
                         .expect("Failed to send event");
                 }
+                PopupId::DumpConfig => match dump_config::write() {
+                    Ok(path) => {
+                        Popup::new()
+                            .with_text(&[&format!("Wrote the config to {}", path.display())])
+                            .with_padding(5)
+                            .create();
+                    }
+                    Err(e) => error!("Failed to dump the config: {}", e),
+                },
             }
         }
     } else if msg == WM_APP && l_param as u32 == WM_RBUTTONUP {
@@ -100,7 +120,7 @@ unsafe extern "system" fn window_cb(
 pub fn create() -> Result<(), util::WinApiResultError> {
     let name = util::to_widestring("WWM Tray");
     let config = CONFIG.lock().unwrap();
-    let app_bar_bg = config.bar.color;
+    let app_bar_bg = config.get_theme().background;
 
     std::thread::spawn(move || unsafe {
         let instance = winapi::um::libloaderapi::GetModuleHandleA(std::ptr::null_mut());
@@ -190,6 +210,7 @@ unsafe fn show_popup_menu(hwnd: HWND) {
 
     let mut exit = util::to_widestring("Exit");
     let mut reload = util::to_widestring("Reload");
+    let mut dump_config = util::to_widestring("Dump config");
 
     InsertMenuW(
         menu,
@@ -199,6 +220,14 @@ unsafe fn show_popup_menu(hwnd: HWND) {
         exit.as_mut_ptr(),
     );
 
+    InsertMenuW(
+        menu,
+        0,
+        MF_BYPOSITION | MF_STRING,
+        PopupId::DumpConfig as usize,
+        dump_config.as_mut_ptr(),
+    );
+
     InsertMenuW(
         menu,
         0,
@@ -1,13 +1,15 @@
+#[cfg(windows)]
 use core::fmt::Debug;
+#[cfg(windows)]
 use thiserror::Error;
+#[cfg(windows)]
 use winapi::shared::windef::HWND;
+#[cfg(windows)]
 use winapi::shared::windef::RECT;
-use winapi::um::wingdi::GetBValue;
-use winapi::um::wingdi::GetGValue;
-use winapi::um::wingdi::GetRValue;
-use winapi::um::wingdi::RGB;
+#[cfg(windows)]
 use winapi::um::winuser::{GetClassNameA, GetWindowTextA};
 
+#[cfg(windows)]
 pub fn get_title_of_window(window_handle: HWND) -> Result<String, WinApiResultError> {
     let mut buffer = [0; 0x200];
 
@@ -22,6 +24,7 @@ pub fn get_title_of_window(window_handle: HWND) -> Result<String, WinApiResultEr
     Ok(bytes_to_string(&buffer))
 }
 
+#[cfg(windows)]
 pub fn bytes_to_string(buffer: &[i8]) -> String {
     buffer
         .iter()
@@ -30,6 +33,7 @@ pub fn bytes_to_string(buffer: &[i8]) -> String {
         .collect::<String>()
 }
 
+#[cfg(windows)]
 pub fn get_class_name_of_window(window_handle: HWND) -> Result<String, WinApiResultError> {
     let mut buffer = [0; 0x200];
 
@@ -48,8 +52,10 @@ pub fn get_class_name_of_window(window_handle: HWND) -> Result<String, WinApiRes
         .collect::<String>())
 }
 
+#[cfg(windows)]
 pub type WinApiResult<T> = Result<T, WinApiResultError>;
 
+#[cfg(windows)]
 #[derive(Debug, Error)]
 pub enum WinApiResultError {
     #[error("Windows Api errored and returned a value of {0}")]
@@ -58,6 +64,7 @@ pub enum WinApiResultError {
     Null,
 }
 
+#[cfg(windows)]
 #[allow(dead_code)]
 pub fn winapi_err_to_result<T>(input: T) -> WinApiResult<T>
 where
@@ -70,6 +77,7 @@ where
     }
 }
 
+#[cfg(windows)]
 pub fn winapi_ptr_to_result<T>(input: *mut T) -> WinApiResult<*mut T> {
     if !input.is_null() {
         Ok(input)
@@ -78,6 +86,7 @@ pub fn winapi_ptr_to_result<T>(input: *mut T) -> WinApiResult<*mut T> {
     }
 }
 
+#[cfg(windows)]
 pub fn winapi_nullable_to_result<T>(input: T) -> WinApiResult<T>
 where
     T: PartialEq<i32>,
@@ -89,10 +98,12 @@ where
     }
 }
 
+#[cfg(windows)]
 pub fn to_widestring(string: &str) -> Vec<u16> {
     string.encode_utf16().chain(std::iter::once(0)).collect()
 }
 
+#[cfg(windows)]
 #[allow(dead_code)]
 pub fn rect_to_string(rect: RECT) -> String {
     format!(
@@ -101,14 +112,26 @@ pub fn rect_to_string(rect: RECT) -> String {
     )
 }
 
+/// Returns the name of this computer or an empty string if it can't be determined
+pub fn get_hostname() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| {
+            std::fs::read_to_string("/etc/hostname")
+                .ok()
+                .map(|name| name.trim().to_string())
+        })
+        .unwrap_or_default()
+}
+
+/// Multiplies each channel of the BGR color with the factor
 pub fn scale_color(color: i32, factor: f64) -> i32 {
-    let mut blue = GetBValue(color as u32);
-    let mut green = GetGValue(color as u32);
-    let mut red = GetRValue(color as u32);
+    let scale = |shift: u32| {
+        let channel = (color as u32 >> shift) & 0xff;
 
-    blue = (blue as f64 * factor).round() as u8;
-    green = (green as f64 * factor).round() as u8;
-    red = (red as f64 * factor).round() as u8;
+        ((channel as f64 * factor).round() as u8 as u32) << shift
+    };
 
-    RGB(red, green, blue) as i32
+    (scale(0) | scale(8) | scale(16)) as i32
 }
@@ -1,5 +1,6 @@
 use winapi::um::winuser::{
-    EVENT_OBJECT_DESTROY, EVENT_OBJECT_HIDE, EVENT_OBJECT_SHOW, EVENT_SYSTEM_FOREGROUND,
+    EVENT_OBJECT_DESTROY, EVENT_OBJECT_HIDE, EVENT_OBJECT_NAMECHANGE, EVENT_OBJECT_SHOW,
+    EVENT_SYSTEM_FOREGROUND,
 };
 
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -9,6 +10,8 @@ pub enum WinEventType {
     ///Takes a bool, which tells us whether to ignore all rules
     Show(bool),
     FocusChange,
+    /// The title of the window changed
+    NameChange,
 }
 
 impl WinEventType {
@@ -21,6 +24,8 @@ impl WinEventType {
             Some(Self::FocusChange)
         } else if v == EVENT_OBJECT_HIDE {
             Some(Self::Hide)
+        } else if v == EVENT_OBJECT_NAMECHANGE {
+            Some(Self::NameChange)
         } else {
             None
         }
@@ -4,8 +4,10 @@ use crate::{display::Display, CONFIG};
 use gwl_ex_style::GwlExStyle;
 use gwl_style::GwlStyle;
 use log::error;
+use winapi::shared::minwindef::DWORD;
 use winapi::shared::windef::HWND;
 use winapi::shared::windef::RECT;
+use winapi::um::dwmapi::DwmSetWindowAttribute;
 use winapi::um::errhandlingapi::GetLastError;
 use winapi::um::winuser::AdjustWindowRectEx;
 use winapi::um::winuser::GetForegroundWindow;
@@ -34,14 +36,20 @@ use winapi::um::{
     psapi::GetModuleFileNameExA,
     winnt::{PROCESS_QUERY_INFORMATION, PROCESS_VM_READ},
     winuser::{
-        GetClientRect, GetSystemMetricsForDpi, GetWindowThreadProcessId, SC_MAXIMIZE, SC_MINIMIZE,
-        SC_RESTORE, WM_CLOSE, WM_PAINT, WM_SYSCOMMAND,
+        GetClientRect, GetSystemMetricsForDpi, GetWindowThreadProcessId, MonitorFromWindow,
+        MONITOR_DEFAULTTONEAREST, SC_MAXIMIZE, SC_MINIMIZE, SC_RESTORE, WM_CLOSE, WM_PAINT,
+        WM_SYSCOMMAND,
     },
 };
 
 pub mod gwl_ex_style;
 pub mod gwl_style;
 
+/// The attribute for the color of the window border, which winapi doesn't know about yet
+const DWMWA_BORDER_COLOR: DWORD = 34;
+/// Lets windows decide about the color of the border
+const DWMWA_COLOR_DEFAULT: u32 = 0xFFFF_FFFF;
+
 #[derive(Clone)]
 pub struct Window {
     pub id: i32,
@@ -83,11 +91,27 @@ impl Window {
         self.reset_style();
         self.update_style();
         self.reset_pos();
+        self.set_border_color(None);
 
         if self.maximized {
             self.maximize();
         }
     }
+
+    /// Moves the window to the position and changes its size (in pixels)
+    pub fn set_pos(&self, x: i32, y: i32, width: i32, height: i32) {
+        unsafe {
+            SetWindowPos(
+                self.id as HWND,
+                std::ptr::null_mut(),
+                x,
+                y,
+                width,
+                height,
+                0,
+            );
+        }
+    }
     pub fn reset_pos(&self) {
         unsafe {
             SetWindowPos(
@@ -101,6 +125,10 @@ impl Window {
             );
         }
     }
+    /// Returns the hmonitor of the display that contains the largest part of the window
+    pub fn get_monitor(&self) -> i32 {
+        unsafe { MonitorFromWindow(self.id as HWND, MONITOR_DEFAULTTONEAREST) as i32 }
+    }
     pub fn get_client_rect(&self) -> RECT {
         let mut rect: RECT = RECT::default();
         unsafe {
@@ -158,7 +186,7 @@ impl Window {
 
             (
                 config.display_app_bar,
-                config.remove_title_bar,
+                rule.remove_title_bar.unwrap_or(config.remove_title_bar),
                 config.bar.height,
                 config.use_border,
             )
@@ -188,8 +216,8 @@ impl Window {
             }
 
             if display_app_bar {
-                top += bar_height;
-                bottom += bar_height;
+                top += display.scale(bar_height);
+                bottom += display.scale(bar_height);
             }
 
             if rule.firefox || rule.chromium || (!remove_title_bar && rule.has_custom_titlebar) {
@@ -321,6 +349,13 @@ impl Window {
             SetWindowLongA(self.id as HWND, GWL_EXSTYLE, self.exstyle.bits());
         }
     }
+    /// Whether the title bar gets removed, which can be overridden by the rule of the window
+    pub fn should_remove_title_bar(&self) -> bool {
+        self.rule
+            .as_ref()
+            .and_then(|rule| rule.remove_title_bar)
+            .unwrap_or_else(|| CONFIG.lock().unwrap().remove_title_bar)
+    }
     pub fn remove_title_bar(&mut self) {
         let rule = self.rule.clone().unwrap_or_default();
         if !rule.chromium && !rule.firefox {
@@ -332,6 +367,22 @@ impl Window {
         }
     }
 
+    /// Changes the color of the border drawn by windows. `None` restores the default color.
+    ///
+    /// Windows only supports this since Windows 11, older versions ignore it.
+    pub fn set_border_color(&self, color: Option<i32>) {
+        let color = color.map(|c| c as u32).unwrap_or(DWMWA_COLOR_DEFAULT);
+
+        unsafe {
+            DwmSetWindowAttribute(
+                self.id as HWND,
+                DWMWA_BORDER_COLOR,
+                &color as *const u32 as *const _,
+                std::mem::size_of::<u32>() as DWORD,
+            );
+        }
+    }
+
     pub fn maximize(&self) {
         unsafe {
             SendMessageA(self.id as HWND, WM_SYSCOMMAND, SC_MAXIMIZE, 0);
@@ -1,6 +1,8 @@
 use crate::{
-    display::get_display_by_idx, event::Event, util, CHANNEL, CONFIG, GRIDS, VISIBLE_WORKSPACES,
-    WORKSPACE_ID,
+    config::rhai::hooks,
+    display::{get_managed_display, is_managed},
+    event::Event,
+    util, CHANNEL, CONFIG, GRIDS, VISIBLE_WORKSPACES, WORKSPACE_ID,
 };
 use log::debug;
 
@@ -38,17 +40,40 @@ pub fn change_workspace(
         .map(|(i, g)| (i, g.clone()))
         .unwrap();
 
+    let mut visible_workspaces = VISIBLE_WORKSPACES.lock().unwrap();
+
     if !ignore_monitor_setting {
-        if new_grid.tiles.is_empty() {
-            if let Some(setting) = workspace_settings.iter().find(|s| s.id == id) {
-                if setting.monitor != -1 {
-                    new_grid.display = get_display_by_idx(setting.monitor);
-                }
+        if let Some(display) = workspace_settings
+            .iter()
+            .find(|s| s.id == id)
+            .and_then(|s| s.get_display())
+        {
+            // the workspace leaves the monitor it is currently visible on
+            if display.hmonitor != new_grid.display.hmonitor
+                && visible_workspaces.get(&new_grid.display.hmonitor) == Some(&new_grid.id)
+            {
+                visible_workspaces.insert(new_grid.display.hmonitor, 0);
             }
+
+            new_grid.displaced_from = None;
+            new_grid.display = display;
         }
     }
 
-    let mut visible_workspaces = VISIBLE_WORKSPACES.lock().unwrap();
+    if !is_managed(new_grid.display.hmonitor) {
+        if let Some(display) = get_managed_display() {
+            debug!(
+                "Display {} is not managed. Moving the workspace to display {}",
+                new_grid.display.name, display.name
+            );
+
+            if visible_workspaces.get(&new_grid.display.hmonitor) == Some(&new_grid.id) {
+                visible_workspaces.insert(new_grid.display.hmonitor, 0);
+            }
+
+            new_grid.display = display;
+        }
+    }
 
     debug!("Drawing the workspace");
     new_grid.draw_grid();
@@ -70,7 +95,11 @@ pub fn change_workspace(
     grids.remove(new_grid_idx);
     grids.insert(new_grid_idx, new_grid);
 
-    *WORKSPACE_ID.lock().unwrap() = id;
+    let prev_id = std::mem::replace(&mut *WORKSPACE_ID.lock().unwrap(), id);
+
+    if prev_id != id {
+        hooks::emit("workspace_changed", vec![id.into()]);
+    }
 
     debug!("Sending redraw-app-bar event");
     CHANNEL
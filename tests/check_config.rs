@@ -0,0 +1,37 @@
+use std::process::{Command, Output};
+
+fn check_config(path: &str) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_nog"))
+        .arg("check-config")
+        .arg(format!("{}/{}", env!("CARGO_MANIFEST_DIR"), path))
+        .output()
+        .expect("Failed to run nog")
+}
+
+#[test]
+fn reports_the_errors_of_a_broken_config() {
+    let output = check_config("tests/fixtures/broken.nog");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.contains("broken.nog:3:"), "{}", stderr);
+    assert!(
+        stderr.contains("error: height has to be of type i32 not string"),
+        "{}",
+        stderr
+    );
+    assert!(stdout.contains("1 error(s), 0 warning(s)"), "{}", stdout);
+}
+
+#[test]
+fn accepts_the_default_config() {
+    let output = check_config("assets/default_config.nog");
+
+    assert_eq!(
+        output.status.code(),
+        Some(0),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+}
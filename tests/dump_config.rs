@@ -0,0 +1,19 @@
+use std::process::Command;
+
+#[test]
+fn overrides_win_even_if_they_set_the_default_value() {
+    let output = Command::new(env!("CARGO_BIN_EXE_nog"))
+        .arg("--config")
+        .arg(format!(
+            "{}/tests/fixtures/overrides.nog",
+            env!("CARGO_MANIFEST_DIR")
+        ))
+        .arg("--dump-config")
+        .output()
+        .expect("Failed to run nog");
+    let stdout = String::from_utf8_lossy(&output.stdout);
+
+    assert_eq!(output.status.code(), Some(0), "{}", stdout);
+    assert!(stdout.contains("\"inner_gap\": 0,"), "{}", stdout);
+    assert!(stdout.contains("when_displays(1)"), "{}", stdout);
+}